@@ -0,0 +1,382 @@
+use crate::events::EventType;
+use crate::patch;
+use crate::synth::engine::Engine;
+use crate::synth::oscillators::{NUMBER_OF_OSCILLATORS, ShapeData, noise, sampler, wavetable};
+use crate::synth::voices::MAXIMUM_NUMBER_OF_VOICES;
+use crate::wav::{self, BitDepth, WavFileWriter};
+use midly::{MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+const BOUNCE_COMMAND: &str = "bounce";
+const BOUNCE_NUMBER_OF_CHANNELS: usize = 2;
+const RENDER_BLOCK_FRAMES: usize = 512;
+const DEFAULT_BOUNCE_SAMPLE_RATE: u32 = 48000;
+const DEFAULT_TAIL_MILLISECONDS: f64 = 2000.0;
+const DEFAULT_MICROSECONDS_PER_BEAT: f64 = 500_000.0;
+const MICROSECONDS_PER_SECOND: f64 = 1_000_000.0;
+const MILLISECONDS_PER_SECOND: f64 = 1000.0;
+const MAXIMUM_MIDI_NOTE_NUMBER: u8 = 127;
+const NOTE_LIST_VELOCITY: u8 = 127;
+const USAGE: &str = "Usage: oscillator_playground bounce --output <file.wav> \
+(--midi <file.mid> | --notes <note:start_ms:length_ms,...>) [--patch <file>] \
+[--sample-rate <hz>] [--bit-depth <16|24|32f>] [--tail <ms>] [--seed <n>]";
+
+struct BounceOptions {
+    output_path: PathBuf,
+    midi_path: Option<PathBuf>,
+    note_list: Option<String>,
+    patch_path: Option<PathBuf>,
+    sample_rate: u32,
+    bit_depth: BitDepth,
+    tail_milliseconds: f64,
+    seed: u64,
+}
+
+struct ScheduledEvent {
+    frame: u64,
+    event: EventType,
+}
+
+pub fn is_bounce_command(arguments: &[String]) -> bool {
+    arguments
+        .first()
+        .is_some_and(|argument| argument == BOUNCE_COMMAND)
+}
+
+pub fn run(arguments: &[String]) -> Result<(), Box<dyn Error>> {
+    let options = get_bounce_options_from_arguments(arguments)?;
+
+    let mut events = match (&options.midi_path, &options.note_list) {
+        (Some(midi_path), None) => get_events_from_midi_file(midi_path, options.sample_rate)?,
+        (None, Some(note_list)) => get_events_from_note_list(note_list, options.sample_rate)?,
+        _ => return Err(format!("Specify either --midi or --notes\n{USAGE}").into()),
+    };
+
+    // Note offs go first so a note repeated on the same frame retriggers cleanly
+    events.sort_by_key(|scheduled_event| {
+        (
+            scheduled_event.frame,
+            matches!(scheduled_event.event, EventType::MidiNoteOn(..)),
+        )
+    });
+
+    let last_event_frame = events
+        .last()
+        .map_or(0, |scheduled_event| scheduled_event.frame);
+    let tail_frames = get_frame_from_seconds(
+        options.tail_milliseconds / MILLISECONDS_PER_SECOND,
+        options.sample_rate,
+    );
+    let total_frames = last_event_frame + tail_frames;
+
+    let mut engine = Engine::new(options.seed, options.sample_rate as f32);
+    if let Some(patch_path) = &options.patch_path {
+        apply_patch(&mut engine, patch_path)?;
+    }
+
+    let mut writer = WavFileWriter::create(
+        &options.output_path,
+        options.sample_rate,
+        BOUNCE_NUMBER_OF_CHANNELS as u16,
+        options.bit_depth,
+    )?;
+
+    let render_start = Instant::now();
+    let mut buffer = vec![0.0; RENDER_BLOCK_FRAMES * BOUNCE_NUMBER_OF_CHANNELS];
+    let mut pending_events = events.into_iter().peekable();
+    let mut current_frame: u64 = 0;
+
+    while current_frame < total_frames {
+        while let Some(scheduled_event) =
+            pending_events.next_if(|scheduled_event| scheduled_event.frame <= current_frame)
+        {
+            engine.handle_event(&scheduled_event.event);
+        }
+
+        // Render up to the next event so every event lands on its exact frame
+        let next_stop_frame = pending_events
+            .peek()
+            .map_or(total_frames, |scheduled_event| scheduled_event.frame)
+            .min(total_frames);
+        let block_frames =
+            (next_stop_frame - current_frame).min(RENDER_BLOCK_FRAMES as u64) as usize;
+        let block = &mut buffer[..block_frames * BOUNCE_NUMBER_OF_CHANNELS];
+
+        block.fill(0.0);
+        engine.render(block, BOUNCE_NUMBER_OF_CHANNELS);
+
+        for sample in block.iter() {
+            writer.write_sample(*sample)?;
+        }
+
+        current_frame += block_frames as u64;
+    }
+
+    writer.finalize()?;
+
+    let rendered_seconds = total_frames as f64 / options.sample_rate as f64;
+    println!(
+        "Bounced {rendered_seconds:.2}s of audio to {} in {:.2}s",
+        options.output_path.display(),
+        render_start.elapsed().as_secs_f64()
+    );
+
+    Ok(())
+}
+
+// The engine leaves loading files to the control thread, so those are done here. A rebuilt wave
+// starts out empty, so whatever was loaded for the oscillator goes back in after a rebuild
+fn apply_patch(engine: &mut Engine, patch_path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut loaded_shape_data: Vec<Option<ShapeData>> = vec![None; NUMBER_OF_OSCILLATORS];
+
+    for event in patch::get_events_from_patch_file(patch_path)? {
+        match event {
+            EventType::LoadWavetable(path, oscillator) => {
+                let wavetable = wavetable::load_wavetable_from_file(Path::new(path.as_str()))?;
+                let shape_data = ShapeData::Wavetable(Arc::new(wavetable));
+                loaded_shape_data[oscillator as usize] = Some(shape_data.clone());
+                engine.swap_shape_data(&mut vec![shape_data; MAXIMUM_NUMBER_OF_VOICES], oscillator);
+            }
+            EventType::LoadSample(path, oscillator) => {
+                let sample = sampler::load_sample_from_file(Path::new(path.as_str()))?;
+                let shape_data = ShapeData::Sample(Arc::new(sample));
+                loaded_shape_data[oscillator as usize] = Some(shape_data.clone());
+                engine.swap_shape_data(&mut vec![shape_data; MAXIMUM_NUMBER_OF_VOICES], oscillator);
+            }
+            EventType::UpdateOscillatorShape(_, oscillator)
+            | EventType::UpdateOscillatorUnisonVoices(_, oscillator) => {
+                engine.handle_event(&event);
+
+                if let Some(shape_data) = &loaded_shape_data[oscillator as usize] {
+                    engine.swap_shape_data(
+                        &mut vec![shape_data.clone(); MAXIMUM_NUMBER_OF_VOICES],
+                        oscillator,
+                    );
+                }
+            }
+            event => engine.handle_event(&event),
+        }
+    }
+
+    Ok(())
+}
+
+fn get_bounce_options_from_arguments(
+    arguments: &[String],
+) -> Result<BounceOptions, Box<dyn Error>> {
+    let mut options = BounceOptions {
+        output_path: PathBuf::new(),
+        midi_path: None,
+        note_list: None,
+        patch_path: None,
+        sample_rate: DEFAULT_BOUNCE_SAMPLE_RATE,
+        bit_depth: BitDepth::default(),
+        tail_milliseconds: DEFAULT_TAIL_MILLISECONDS,
+        seed: noise::DEFAULT_NOISE_SEED,
+    };
+
+    let mut arguments = arguments.iter().skip(1);
+
+    while let Some(flag) = arguments.next() {
+        let value = arguments
+            .next()
+            .ok_or_else(|| format!("Missing value for {flag}\n{USAGE}"))?;
+
+        match flag.as_str() {
+            "--output" | "-o" => options.output_path = PathBuf::from(value),
+            "--midi" | "-m" => options.midi_path = Some(PathBuf::from(value)),
+            "--notes" | "-n" => options.note_list = Some(value.clone()),
+            "--patch" | "-p" => options.patch_path = Some(PathBuf::from(value)),
+            "--sample-rate" | "-r" => options.sample_rate = value.parse()?,
+            "--bit-depth" | "-b" => {
+                options.bit_depth = wav::get_bit_depth_from_name(value)
+                    .ok_or_else(|| format!("Unsupported bit depth {value}\n{USAGE}"))?;
+            }
+            "--tail" | "-t" => options.tail_milliseconds = value.parse()?,
+            "--seed" | "-s" => options.seed = value.parse()?,
+            _ => return Err(format!("Unknown option {flag}\n{USAGE}").into()),
+        }
+    }
+
+    if options.output_path.as_os_str().is_empty() {
+        return Err(format!("Missing --output\n{USAGE}").into());
+    }
+
+    if options.sample_rate == 0 {
+        return Err("The sample rate must be greater than zero".into());
+    }
+
+    Ok(options)
+}
+
+fn get_events_from_note_list(
+    note_list: &str,
+    sample_rate: u32,
+) -> Result<Vec<ScheduledEvent>, Box<dyn Error>> {
+    let mut events = Vec::new();
+
+    for note in note_list
+        .split(',')
+        .map(str::trim)
+        .filter(|note| !note.is_empty())
+    {
+        let fields: Vec<&str> = note.split(':').collect();
+
+        let [note_number, start_milliseconds, length_milliseconds] = fields[..] else {
+            return Err(
+                format!("Notes must be formatted as note:start_ms:length_ms, got {note}").into(),
+            );
+        };
+
+        let note_number: u8 = note_number.parse()?;
+        if note_number > MAXIMUM_MIDI_NOTE_NUMBER {
+            return Err(format!("Note number {note_number} is outside the MIDI range").into());
+        }
+
+        let start_milliseconds: f64 = start_milliseconds.parse()?;
+        let length_milliseconds: f64 = length_milliseconds.parse()?;
+
+        // A negative length would put the note off before the note on and hang the note
+        if !is_valid_note_time(start_milliseconds) || !is_valid_note_time(length_milliseconds) {
+            return Err(format!(
+                "Note start and length must be zero or more milliseconds, got {note}"
+            )
+            .into());
+        }
+
+        let start_seconds = start_milliseconds / MILLISECONDS_PER_SECOND;
+        let length_seconds = length_milliseconds / MILLISECONDS_PER_SECOND;
+
+        events.push(ScheduledEvent {
+            frame: get_frame_from_seconds(start_seconds, sample_rate),
+            event: EventType::MidiNoteOn(note_number, NOTE_LIST_VELOCITY),
+        });
+        events.push(ScheduledEvent {
+            frame: get_frame_from_seconds(start_seconds + length_seconds, sample_rate),
+            event: EventType::MidiNoteOff(note_number),
+        });
+    }
+
+    Ok(events)
+}
+
+fn is_valid_note_time(milliseconds: f64) -> bool {
+    milliseconds.is_finite() && milliseconds >= 0.0
+}
+
+fn get_events_from_midi_file(
+    midi_path: &Path,
+    sample_rate: u32,
+) -> Result<Vec<ScheduledEvent>, Box<dyn Error>> {
+    let midi_file_bytes = fs::read(midi_path)?;
+    let smf = Smf::parse(&midi_file_bytes)?;
+
+    // Merge every track onto one absolute tick timeline so tempo changes apply to all of them
+    let mut timeline = Vec::new();
+    for track in &smf.tracks {
+        let mut absolute_tick: u64 = 0;
+        for track_event in track {
+            absolute_tick += track_event.delta.as_int() as u64;
+            timeline.push((absolute_tick, track_event.kind));
+        }
+    }
+    timeline.sort_by_key(|(tick, _)| *tick);
+
+    let mut events = Vec::new();
+    let mut microseconds_per_beat = DEFAULT_MICROSECONDS_PER_BEAT;
+    let mut last_tick: u64 = 0;
+    let mut seconds = 0.0;
+
+    for (tick, kind) in timeline {
+        let seconds_per_tick = match smf.header.timing {
+            Timing::Metrical(ticks_per_beat) => {
+                microseconds_per_beat / MICROSECONDS_PER_SECOND / ticks_per_beat.as_int() as f64
+            }
+            Timing::Timecode(frames_per_second, ticks_per_frame) => {
+                1.0 / (frames_per_second.as_f32() as f64 * ticks_per_frame as f64)
+            }
+        };
+
+        seconds += (tick - last_tick) as f64 * seconds_per_tick;
+        last_tick = tick;
+        let frame = get_frame_from_seconds(seconds, sample_rate);
+
+        match kind {
+            TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => {
+                microseconds_per_beat = tempo.as_int() as f64;
+            }
+            TrackEventKind::Midi {
+                message: MidiMessage::NoteOn { key, vel },
+                ..
+            } => {
+                let event = if vel.as_int() == 0 {
+                    EventType::MidiNoteOff(key.as_int())
+                } else {
+                    EventType::MidiNoteOn(key.as_int(), vel.as_int())
+                };
+                events.push(ScheduledEvent { frame, event });
+            }
+            TrackEventKind::Midi {
+                message: MidiMessage::NoteOff { key, .. },
+                ..
+            } => {
+                events.push(ScheduledEvent {
+                    frame,
+                    event: EventType::MidiNoteOff(key.as_int()),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(events)
+}
+
+fn get_frame_from_seconds(seconds: f64, sample_rate: u32) -> u64 {
+    (seconds.max(0.0) * sample_rate as f64).round() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{SampleFormat, WavReader};
+
+    #[test]
+    fn bounces_a_note_list_to_the_expected_length() {
+        let output_path = std::env::temp_dir().join(format!(
+            "oscillator_playground_{}_bounce.wav",
+            std::process::id()
+        ));
+        let arguments = [
+            BOUNCE_COMMAND,
+            "--output",
+            output_path.to_str().unwrap(),
+            "--notes",
+            "60:0:100,64:50:100",
+            "--sample-rate",
+            "8000",
+            "--bit-depth",
+            "16",
+            "--tail",
+            "100",
+        ]
+        .map(String::from);
+
+        run(&arguments).unwrap();
+        let (spec, number_of_frames) = WavReader::open(&output_path)
+            .map(|reader| (reader.spec(), reader.duration()))
+            .unwrap();
+        fs::remove_file(&output_path).unwrap();
+
+        assert_eq!(spec.channels, BOUNCE_NUMBER_OF_CHANNELS as u16);
+        assert_eq!(spec.sample_rate, 8000);
+        assert_eq!(spec.bits_per_sample, 16);
+        assert_eq!(spec.sample_format, SampleFormat::Int);
+        // The last note ends at 150ms, then the 100ms tail
+        assert_eq!(number_of_frames, 2000);
+    }
+}
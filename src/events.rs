@@ -6,13 +6,37 @@ use slint::SharedString;
 pub enum EventType {
     UpdateOscillatorShape(SharedString, i32),
     UpdateOscillatorTuning(i32, i32),
+    UpdateOscillatorOctave(i32, i32),
+    UpdateOscillatorFineTune(f32, i32),
+    UpdateSubOctave(i32),
+    UpdateMasterTune(f32),
     UpdateOscillatorLevel(f32, i32),
     UpdateOscillatorSpecificParameters((f32, f32), i32),
+    UpdateOscillatorNumberOfVoices(i32, i32),
+    UpdateOscillatorStereoSpread(f32, i32),
+    UpdateOscillatorSyncSource(SharedString, i32),
+    UpdateOscillatorRingModSource(SharedString, i32),
+    UpdateOscillatorFMSource(SharedString, i32),
+    UpdateOscillatorFMMode(SharedString, i32),
+    UpdateOscillatorFMAmount(f32, i32),
+    UpdateFMAlgorithm(i32, i32),
+    UpdateFMOperatorParameter(SharedString, f32, i32, i32),
+    UpdateAdditivePreset(SharedString, i32),
+    UpdateAdditiveStretch(f32, i32),
+    UpdateSamplerRootKey(i32, i32),
+    UpdateSamplerLoopMode(SharedString, i32),
+    UpdateSamplerInterpolation(SharedString, i32),
     UpdateOscillatorDriveAmount(f32, i32),
     UpdateOscillatorModFreq(f32, i32),
     UpdateOscillatorModAmount(f32, i32),
-    UpdateOscillatorDetuneActive(bool, f32),
-    UpdateOscillatorDetuneValue(f32),
+    UpdateOscillatorUnisonVoices(i32, i32),
+    UpdateOscillatorUnisonDetune(f32, i32),
+    UpdateOscillatorUnisonWidth(f32, i32),
+    UpdateOscillatorUnisonRandomPhase(bool, i32),
+    UpdateOscillatorFilterSend(SharedString, i32),
+    UpdateOscillatorQuality(SharedString),
+    LoadWavetable(SharedString, i32),
+    LoadSample(SharedString, i32),
     UpdateOutputLevel(i32),
     UpdateOutputLevelConstant(bool),
     UpdateOutputPan(f32),
@@ -24,9 +48,20 @@ pub enum EventType {
     UpdateAmpModeEnvelopeEnabled(bool),
     UpdateGateDutyCycle(f32),
     UpdateGateNoteLength(i32),
-    UpdateFilterCutoffValue(i32),
-    UpdateFilterResonanceValue(f32),
-    UpdateFilterNumberOfPoles(i32),
+    UpdateFilterCutoffValue(i32, i32),
+    UpdateFilterResonanceValue(f32, i32),
+    UpdateFilterNumberOfPoles(i32, i32),
+    UpdateFilterType(SharedString, i32),
+    UpdateFilterModel(SharedString, i32),
+    UpdateFilterDrive(f32, i32),
+    UpdateFilterRouting(SharedString),
+    UpdateFilterEnvelopeAttack(i32),
+    UpdateFilterEnvelopeDecay(i32),
+    UpdateFilterEnvelopeSustain(f32),
+    UpdateFilterEnvelopeRelease(i32),
+    UpdateFilterEnvelopeAmount(f32),
+    UpdateFilterKeyTracking(f32),
+    UpdateFilterVelocityAmount(f32),
     UpdateAutoPanEnabled(bool),
     UpdateAutoPanSpeed(f32),
     UpdateAutoPanWidth(f32),
@@ -58,11 +93,20 @@ pub enum EventType {
     UpdateClipperThreshold(f32),
     ResyncOscillators,
     ResyncOscillatorLFOs,
+    UpdateNumberOfVoices(i32),
+    UpdateVoiceStealingMode(SharedString),
+    UpdateVoiceMode(SharedString),
+    UpdateNotePriority(SharedString),
+    UpdateLegatoEnabled(bool),
+    UpdateGlideTime(i32),
+    UpdateGlideCurve(SharedString),
+    UpdateGlideMode(SharedString),
     ArpeggiatorActive(bool),
     ArpeggiatorAddNote(i32),
     ArpeggiatorRemoveNote(i32),
     ArpeggiatorRandomEnabled(bool),
-    MidiNoteOn(u8),
+    ArpeggiatorGlideEnabled(bool),
+    MidiNoteOn(u8, u8),
     MidiNoteOff(u8),
     UpdateMidiPortList(Vec<String>),
     UpdateMidiPort(i32),
@@ -70,6 +114,8 @@ pub enum EventType {
     UpdateOutputDeviceList(DeviceList),
     UpdateAudioDevice(String),
     UpdateAudioChannels(String, String),
+    StartRecording,
+    StopRecording,
 }
 
 pub struct Events {
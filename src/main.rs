@@ -1,18 +1,31 @@
+mod bounce;
 mod device_manager;
 mod events;
 mod midi;
+mod patch;
 mod synth;
 mod ui;
+mod wav;
 
 use crate::device_manager::DeviceManager;
 use crate::midi::Midi;
 use crate::synth::Synth;
 use crate::ui::UI;
 use slint::ComponentHandle;
-use std::thread;
+use std::{env, process, thread};
 
 slint::include_modules!();
 fn main() -> Result<(), slint::PlatformError> {
+    // Offline bounces run without an audio device or a window
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    if bounce::is_bounce_command(&arguments) {
+        if let Err(error) = bounce::run(&arguments) {
+            eprintln!("Error bouncing audio: {error}");
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
     let application = AppWindow::new()?;
 
     let events = events::Events::new();
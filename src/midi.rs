@@ -7,6 +7,7 @@ use std::thread;
 
 const MIDI_STATUS_BYTE_INDEX: usize = 0;
 const MIDI_NOTE_NUMBER_BYTE_INDEX: usize = 1;
+const MIDI_VELOCITY_BYTE_INDEX: usize = 2;
 const MIDI_CHANNEL_FOR_OMNI: i32 = 0;
 const MIDI_CHANNEL_OFFSET_USER_VS_INDEX: i32 = 1;
 const MIDI_INPUT_CLIENT_NAME: &str = "Accidental Synth Input";
@@ -123,9 +124,17 @@ fn create_new_midi_listener(
 
                 match message_type {
                     MessageType::NoteOn => {
-                        if let Err(error) = synth_sender.send(
-                            EventType::MidiNoteOn(message[MIDI_NOTE_NUMBER_BYTE_INDEX]).clone(),
-                        ) {
+                        let note_number = message[MIDI_NOTE_NUMBER_BYTE_INDEX];
+                        let velocity = message.get(MIDI_VELOCITY_BYTE_INDEX).copied().unwrap_or(0);
+
+                        // A note on with no velocity is how many keyboards send a note off
+                        let event = if velocity == 0 {
+                            EventType::MidiNoteOff(note_number)
+                        } else {
+                            EventType::MidiNoteOn(note_number, velocity)
+                        };
+
+                        if let Err(error) = synth_sender.send(event) {
                             eprintln!("Error sending event: {error}",);
                         }
                     }
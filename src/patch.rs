@@ -0,0 +1,553 @@
+use crate::events::EventType;
+use crate::synth::filter::NUMBER_OF_FILTERS;
+use crate::synth::oscillators::NUMBER_OF_OSCILLATORS;
+use slint::SharedString;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::vec;
+
+const PATCH_COMMENT_PREFIX: char = '#';
+
+// A patch is a list of the same events the UI sends, one per line and written the way they print,
+// like UpdateOscillatorShape("Saw", 0) or UpdateFilterCutoffValue(800, 0). They are applied in
+// order to a fresh engine, so anything left out stays at its default
+pub fn get_events_from_patch_file(patch_path: &Path) -> Result<Vec<EventType>, Box<dyn Error>> {
+    let patch = fs::read_to_string(patch_path)?;
+
+    patch
+        .lines()
+        .enumerate()
+        .map(|(line_index, line)| (line_index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with(PATCH_COMMENT_PREFIX))
+        .map(|(line_number, line)| {
+            get_event_from_patch_line(line)
+                .map_err(|error| format!("Patch line {line_number}: {error}").into())
+        })
+        .collect()
+}
+
+fn get_event_from_patch_line(line: &str) -> Result<EventType, Box<dyn Error>> {
+    let (name, arguments) = match line.split_once('(') {
+        Some((name, arguments)) => {
+            let arguments = arguments
+                .strip_suffix(')')
+                .ok_or_else(|| format!("Missing closing bracket in {line}"))?;
+            (name.trim(), split_arguments(arguments))
+        }
+        None => (line, Vec::new()),
+    };
+
+    let mut arguments = PatchArguments {
+        name,
+        arguments: arguments.into_iter(),
+    };
+
+    let event = match name {
+        "UpdateOscillatorShape" => {
+            EventType::UpdateOscillatorShape(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorTuning" => {
+            EventType::UpdateOscillatorTuning(arguments.integer()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorOctave" => {
+            EventType::UpdateOscillatorOctave(arguments.integer()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorFineTune" => {
+            EventType::UpdateOscillatorFineTune(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateSubOctave" => EventType::UpdateSubOctave(arguments.integer()?),
+        "UpdateMasterTune" => EventType::UpdateMasterTune(arguments.float()?),
+        "UpdateOscillatorLevel" => {
+            EventType::UpdateOscillatorLevel(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorSpecificParameters" => EventType::UpdateOscillatorSpecificParameters(
+            arguments.float_pair()?,
+            arguments.oscillator()?,
+        ),
+        "UpdateOscillatorNumberOfVoices" => {
+            EventType::UpdateOscillatorNumberOfVoices(arguments.integer()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorStereoSpread" => {
+            EventType::UpdateOscillatorStereoSpread(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorSyncSource" => {
+            EventType::UpdateOscillatorSyncSource(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorRingModSource" => {
+            EventType::UpdateOscillatorRingModSource(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorFMSource" => {
+            EventType::UpdateOscillatorFMSource(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorFMMode" => {
+            EventType::UpdateOscillatorFMMode(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorFMAmount" => {
+            EventType::UpdateOscillatorFMAmount(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateFMAlgorithm" => {
+            EventType::UpdateFMAlgorithm(arguments.integer()?, arguments.oscillator()?)
+        }
+        "UpdateFMOperatorParameter" => EventType::UpdateFMOperatorParameter(
+            arguments.text()?,
+            arguments.float()?,
+            arguments.integer()?,
+            arguments.oscillator()?,
+        ),
+        "UpdateAdditivePreset" => {
+            EventType::UpdateAdditivePreset(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateAdditiveStretch" => {
+            EventType::UpdateAdditiveStretch(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateSamplerRootKey" => {
+            EventType::UpdateSamplerRootKey(arguments.integer()?, arguments.oscillator()?)
+        }
+        "UpdateSamplerLoopMode" => {
+            EventType::UpdateSamplerLoopMode(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateSamplerInterpolation" => {
+            EventType::UpdateSamplerInterpolation(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorDriveAmount" => {
+            EventType::UpdateOscillatorDriveAmount(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorModFreq" => {
+            EventType::UpdateOscillatorModFreq(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorModAmount" => {
+            EventType::UpdateOscillatorModAmount(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorUnisonVoices" => {
+            EventType::UpdateOscillatorUnisonVoices(arguments.integer()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorUnisonDetune" => {
+            EventType::UpdateOscillatorUnisonDetune(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorUnisonWidth" => {
+            EventType::UpdateOscillatorUnisonWidth(arguments.float()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorUnisonRandomPhase" => EventType::UpdateOscillatorUnisonRandomPhase(
+            arguments.boolean()?,
+            arguments.oscillator()?,
+        ),
+        "UpdateOscillatorFilterSend" => {
+            EventType::UpdateOscillatorFilterSend(arguments.text()?, arguments.oscillator()?)
+        }
+        "UpdateOscillatorQuality" => EventType::UpdateOscillatorQuality(arguments.text()?),
+        "LoadWavetable" => EventType::LoadWavetable(arguments.text()?, arguments.oscillator()?),
+        "LoadSample" => EventType::LoadSample(arguments.text()?, arguments.oscillator()?),
+        "UpdateOutputLevel" => EventType::UpdateOutputLevel(arguments.integer()?),
+        "UpdateOutputLevelConstant" => EventType::UpdateOutputLevelConstant(arguments.boolean()?),
+        "UpdateOutputPan" => EventType::UpdateOutputPan(arguments.float()?),
+        "UpdateEnvelopeAttack" => EventType::UpdateEnvelopeAttack(arguments.integer()?),
+        "UpdateEnvelopeDecay" => EventType::UpdateEnvelopeDecay(arguments.integer()?),
+        "UpdateEnvelopeRelease" => EventType::UpdateEnvelopeRelease(arguments.integer()?),
+        "UpdateEnvelopeSustainLevel" => EventType::UpdateEnvelopeSustainLevel(arguments.integer()?),
+        "UpdateADSRNoteLength" => EventType::UpdateADSRNoteLength(arguments.integer()?),
+        "UpdateAmpModeEnvelopeEnabled" => {
+            EventType::UpdateAmpModeEnvelopeEnabled(arguments.boolean()?)
+        }
+        "UpdateGateDutyCycle" => EventType::UpdateGateDutyCycle(arguments.float()?),
+        "UpdateGateNoteLength" => EventType::UpdateGateNoteLength(arguments.integer()?),
+        "UpdateFilterCutoffValue" => {
+            EventType::UpdateFilterCutoffValue(arguments.integer()?, arguments.filter()?)
+        }
+        "UpdateFilterResonanceValue" => {
+            EventType::UpdateFilterResonanceValue(arguments.float()?, arguments.filter()?)
+        }
+        "UpdateFilterNumberOfPoles" => {
+            EventType::UpdateFilterNumberOfPoles(arguments.integer()?, arguments.filter()?)
+        }
+        "UpdateFilterType" => EventType::UpdateFilterType(arguments.text()?, arguments.filter()?),
+        "UpdateFilterModel" => EventType::UpdateFilterModel(arguments.text()?, arguments.filter()?),
+        "UpdateFilterDrive" => {
+            EventType::UpdateFilterDrive(arguments.float()?, arguments.filter()?)
+        }
+        "UpdateFilterRouting" => EventType::UpdateFilterRouting(arguments.text()?),
+        "UpdateFilterEnvelopeAttack" => EventType::UpdateFilterEnvelopeAttack(arguments.integer()?),
+        "UpdateFilterEnvelopeDecay" => EventType::UpdateFilterEnvelopeDecay(arguments.integer()?),
+        "UpdateFilterEnvelopeSustain" => EventType::UpdateFilterEnvelopeSustain(arguments.float()?),
+        "UpdateFilterEnvelopeRelease" => {
+            EventType::UpdateFilterEnvelopeRelease(arguments.integer()?)
+        }
+        "UpdateFilterEnvelopeAmount" => EventType::UpdateFilterEnvelopeAmount(arguments.float()?),
+        "UpdateFilterKeyTracking" => EventType::UpdateFilterKeyTracking(arguments.float()?),
+        "UpdateFilterVelocityAmount" => EventType::UpdateFilterVelocityAmount(arguments.float()?),
+        "UpdateAutoPanEnabled" => EventType::UpdateAutoPanEnabled(arguments.boolean()?),
+        "UpdateAutoPanSpeed" => EventType::UpdateAutoPanSpeed(arguments.float()?),
+        "UpdateAutoPanWidth" => EventType::UpdateAutoPanWidth(arguments.float()?),
+        "UpdateTremoloEnabled" => EventType::UpdateTremoloEnabled(arguments.boolean()?),
+        "UpdateTremoloSpeed" => EventType::UpdateTremoloSpeed(arguments.float()?),
+        "UpdateTremoloDepth" => EventType::UpdateTremoloDepth(arguments.float()?),
+        "UpdateFilterModEnabled" => EventType::UpdateFilterModEnabled(arguments.boolean()?),
+        "UpdateFilterModSpeed" => EventType::UpdateFilterModSpeed(arguments.float()?),
+        "UpdateFilterModAmount" => EventType::UpdateFilterModAmount(arguments.float()?),
+        "UpdateFilterModShape" => EventType::UpdateFilterModShape(arguments.text()?),
+        "UpdatePhaserEnabled" => EventType::UpdatePhaserEnabled(arguments.boolean()?),
+        "UpdatePhaserSpeed" => EventType::UpdatePhaserSpeed(arguments.float()?),
+        "UpdatePhaserAmount" => EventType::UpdatePhaserAmount(arguments.float()?),
+        "UpdateBitCrusherEnabled" => EventType::UpdateBitCrusherEnabled(arguments.boolean()?),
+        "UpdateBitCrusherAmount" => EventType::UpdateBitCrusherAmount(arguments.integer()?),
+        "UpdateSaturationEnabled" => EventType::UpdateSaturationEnabled(arguments.boolean()?),
+        "UpdateSaturationMode" => EventType::UpdateSaturationMode(arguments.text()?),
+        "UpdateSaturationAmount" => EventType::UpdateSaturationAmount(arguments.float()?),
+        "UpdateMakeupGain" => EventType::UpdateMakeupGain(arguments.integer()?),
+        "UpdateCompressorActive" => EventType::UpdateCompressorActive(arguments.boolean()?),
+        "UpdateCompressorThreshold" => EventType::UpdateCompressorThreshold(arguments.float()?),
+        "UpdateCompressorRatio" => EventType::UpdateCompressorRatio(arguments.float()?),
+        "UpdateWaveFolderActive" => EventType::UpdateWaveFolderActive(arguments.boolean()?),
+        "UpdateWaveFolderThreshold" => EventType::UpdateWaveFolderThreshold(arguments.float()?),
+        "UpdateWaveFolderRatio" => EventType::UpdateWaveFolderRatio(arguments.float()?),
+        "UpdateLimiterActive" => EventType::UpdateLimiterActive(arguments.boolean()?),
+        "UpdateLimiterThreshold" => EventType::UpdateLimiterThreshold(arguments.float()?),
+        "UpdateClipperActive" => EventType::UpdateClipperActive(arguments.boolean()?),
+        "UpdateClipperThreshold" => EventType::UpdateClipperThreshold(arguments.float()?),
+        "ResyncOscillators" => EventType::ResyncOscillators,
+        "ResyncOscillatorLFOs" => EventType::ResyncOscillatorLFOs,
+        "UpdateNumberOfVoices" => EventType::UpdateNumberOfVoices(arguments.integer()?),
+        "UpdateVoiceStealingMode" => EventType::UpdateVoiceStealingMode(arguments.text()?),
+        "UpdateVoiceMode" => EventType::UpdateVoiceMode(arguments.text()?),
+        "UpdateNotePriority" => EventType::UpdateNotePriority(arguments.text()?),
+        "UpdateLegatoEnabled" => EventType::UpdateLegatoEnabled(arguments.boolean()?),
+        "UpdateGlideTime" => EventType::UpdateGlideTime(arguments.integer()?),
+        "UpdateGlideCurve" => EventType::UpdateGlideCurve(arguments.text()?),
+        "UpdateGlideMode" => EventType::UpdateGlideMode(arguments.text()?),
+        "ArpeggiatorActive" => EventType::ArpeggiatorActive(arguments.boolean()?),
+        "ArpeggiatorAddNote" => EventType::ArpeggiatorAddNote(arguments.integer()?),
+        "ArpeggiatorRemoveNote" => EventType::ArpeggiatorRemoveNote(arguments.integer()?),
+        "ArpeggiatorRandomEnabled" => EventType::ArpeggiatorRandomEnabled(arguments.boolean()?),
+        "ArpeggiatorGlideEnabled" => EventType::ArpeggiatorGlideEnabled(arguments.boolean()?),
+        // Notes, devices and recording aren't part of a sound
+        _ => return Err(format!("Unknown patch setting {name}").into()),
+    };
+
+    arguments.finish()?;
+
+    Ok(event)
+}
+
+struct PatchArguments<'a> {
+    name: &'a str,
+    arguments: vec::IntoIter<String>,
+}
+
+impl PatchArguments<'_> {
+    fn next_argument(&mut self) -> Result<String, Box<dyn Error>> {
+        self.arguments
+            .next()
+            .ok_or_else(|| format!("Missing a value for {}", self.name).into())
+    }
+
+    fn integer(&mut self) -> Result<i32, Box<dyn Error>> {
+        Ok(self.next_argument()?.parse()?)
+    }
+
+    fn oscillator(&mut self) -> Result<i32, Box<dyn Error>> {
+        self.index(NUMBER_OF_OSCILLATORS, "oscillator")
+    }
+
+    fn filter(&mut self) -> Result<i32, Box<dyn Error>> {
+        self.index(NUMBER_OF_FILTERS, "filter")
+    }
+
+    // Oscillator and filter numbers index straight into the engine, so they are checked here
+    fn index(&mut self, count: usize, index_name: &str) -> Result<i32, Box<dyn Error>> {
+        let index = self.integer()?;
+        if usize::try_from(index).is_ok_and(|index| index < count) {
+            return Ok(index);
+        }
+
+        Err(format!(
+            "{} needs {index_name} 0 to {}, got {index}",
+            self.name,
+            count - 1
+        )
+        .into())
+    }
+
+    fn float(&mut self) -> Result<f32, Box<dyn Error>> {
+        let value: f32 = self.next_argument()?.parse()?;
+        if !value.is_finite() {
+            return Err(format!("{} needs a finite number", self.name).into());
+        }
+
+        Ok(value)
+    }
+
+    fn boolean(&mut self) -> Result<bool, Box<dyn Error>> {
+        Ok(self.next_argument()?.parse()?)
+    }
+
+    fn text(&mut self) -> Result<SharedString, Box<dyn Error>> {
+        let argument = self.next_argument()?;
+        let text = argument
+            .strip_prefix('"')
+            .and_then(|argument| argument.strip_suffix('"'))
+            .ok_or_else(|| format!("{} needs quoted text, got {argument}", self.name))?;
+
+        Ok(SharedString::from(unescape(text)))
+    }
+
+    fn float_pair(&mut self) -> Result<(f32, f32), Box<dyn Error>> {
+        let argument = self.next_argument()?;
+        let pair = argument
+            .strip_prefix('(')
+            .and_then(|argument| argument.strip_suffix(')'))
+            .ok_or_else(|| format!("{} needs a (value, value) pair", self.name))?;
+
+        let mut pair = PatchArguments {
+            name: self.name,
+            arguments: split_arguments(pair).into_iter(),
+        };
+        let values = (pair.float()?, pair.float()?);
+        pair.finish()?;
+
+        Ok(values)
+    }
+
+    fn finish(&mut self) -> Result<(), Box<dyn Error>> {
+        match self.arguments.next() {
+            Some(argument) => Err(format!("Unexpected value {argument} for {}", self.name).into()),
+            None => Ok(()),
+        }
+    }
+}
+
+// Splits on the commas between arguments, leaving the ones inside quotes and brackets alone
+fn split_arguments(arguments: &str) -> Vec<String> {
+    let mut split_arguments = Vec::new();
+    let mut argument = String::new();
+    let mut bracket_depth = 0;
+    let mut is_quoted = false;
+    let mut is_escaped = false;
+
+    for character in arguments.chars() {
+        match character {
+            _ if is_escaped => is_escaped = false,
+            '\\' if is_quoted => is_escaped = true,
+            '"' => is_quoted = !is_quoted,
+            '(' if !is_quoted => bracket_depth += 1,
+            ')' if !is_quoted => bracket_depth -= 1,
+            ',' if !is_quoted && bracket_depth == 0 => {
+                split_arguments.push(argument.trim().to_string());
+                argument.clear();
+                continue;
+            }
+            _ => {}
+        }
+
+        argument.push(character);
+    }
+
+    if !argument.trim().is_empty() {
+        split_arguments.push(argument.trim().to_string());
+    }
+
+    split_arguments
+}
+
+// Quoted text is written the way it prints, with quotes and backslashes escaped
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => unescaped.extend(characters.next()),
+            _ => unescaped.push(character),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn get_patch_file_path(contents: &str, file_name: &str) -> PathBuf {
+        let patch_path = std::env::temp_dir().join(format!(
+            "oscillator_playground_{}_{file_name}",
+            std::process::id()
+        ));
+        fs::write(&patch_path, contents).unwrap();
+        patch_path
+    }
+
+    fn get_every_patch_event() -> Vec<EventType> {
+        vec![
+            EventType::UpdateOscillatorShape(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorTuning(1, 3),
+            EventType::UpdateOscillatorOctave(1, 3),
+            EventType::UpdateOscillatorFineTune(0.5, 3),
+            EventType::UpdateSubOctave(1),
+            EventType::UpdateMasterTune(0.5),
+            EventType::UpdateOscillatorLevel(0.5, 3),
+            EventType::UpdateOscillatorSpecificParameters((0.25, 0.75), 3),
+            EventType::UpdateOscillatorNumberOfVoices(1, 3),
+            EventType::UpdateOscillatorStereoSpread(0.5, 3),
+            EventType::UpdateOscillatorSyncSource(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorRingModSource(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorFMSource(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorFMMode(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorFMAmount(0.5, 3),
+            EventType::UpdateFMAlgorithm(1, 3),
+            EventType::UpdateFMOperatorParameter(SharedString::from("Saw"), 0.5, 1, 3),
+            EventType::UpdateAdditivePreset(SharedString::from("Saw"), 3),
+            EventType::UpdateAdditiveStretch(0.5, 3),
+            EventType::UpdateSamplerRootKey(1, 3),
+            EventType::UpdateSamplerLoopMode(SharedString::from("Saw"), 3),
+            EventType::UpdateSamplerInterpolation(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorDriveAmount(0.5, 3),
+            EventType::UpdateOscillatorModFreq(0.5, 3),
+            EventType::UpdateOscillatorModAmount(0.5, 3),
+            EventType::UpdateOscillatorUnisonVoices(1, 3),
+            EventType::UpdateOscillatorUnisonDetune(0.5, 3),
+            EventType::UpdateOscillatorUnisonWidth(0.5, 3),
+            EventType::UpdateOscillatorUnisonRandomPhase(true, 3),
+            EventType::UpdateOscillatorFilterSend(SharedString::from("Saw"), 3),
+            EventType::UpdateOscillatorQuality(SharedString::from("Saw")),
+            EventType::LoadWavetable(SharedString::from("Saw"), 3),
+            EventType::LoadSample(SharedString::from("Saw"), 3),
+            EventType::UpdateOutputLevel(1),
+            EventType::UpdateOutputLevelConstant(true),
+            EventType::UpdateOutputPan(0.5),
+            EventType::UpdateEnvelopeAttack(1),
+            EventType::UpdateEnvelopeDecay(1),
+            EventType::UpdateEnvelopeRelease(1),
+            EventType::UpdateEnvelopeSustainLevel(1),
+            EventType::UpdateADSRNoteLength(1),
+            EventType::UpdateAmpModeEnvelopeEnabled(true),
+            EventType::UpdateGateDutyCycle(0.5),
+            EventType::UpdateGateNoteLength(1),
+            EventType::UpdateFilterCutoffValue(1, 1),
+            EventType::UpdateFilterResonanceValue(0.5, 1),
+            EventType::UpdateFilterNumberOfPoles(1, 1),
+            EventType::UpdateFilterType(SharedString::from("Saw"), 1),
+            EventType::UpdateFilterModel(SharedString::from("Saw"), 1),
+            EventType::UpdateFilterDrive(0.5, 1),
+            EventType::UpdateFilterRouting(SharedString::from("Saw")),
+            EventType::UpdateFilterEnvelopeAttack(1),
+            EventType::UpdateFilterEnvelopeDecay(1),
+            EventType::UpdateFilterEnvelopeSustain(0.5),
+            EventType::UpdateFilterEnvelopeRelease(1),
+            EventType::UpdateFilterEnvelopeAmount(0.5),
+            EventType::UpdateFilterKeyTracking(0.5),
+            EventType::UpdateFilterVelocityAmount(0.5),
+            EventType::UpdateAutoPanEnabled(true),
+            EventType::UpdateAutoPanSpeed(0.5),
+            EventType::UpdateAutoPanWidth(0.5),
+            EventType::UpdateTremoloEnabled(true),
+            EventType::UpdateTremoloSpeed(0.5),
+            EventType::UpdateTremoloDepth(0.5),
+            EventType::UpdateFilterModEnabled(true),
+            EventType::UpdateFilterModSpeed(0.5),
+            EventType::UpdateFilterModAmount(0.5),
+            EventType::UpdateFilterModShape(SharedString::from("Saw")),
+            EventType::UpdatePhaserEnabled(true),
+            EventType::UpdatePhaserSpeed(0.5),
+            EventType::UpdatePhaserAmount(0.5),
+            EventType::UpdateBitCrusherEnabled(true),
+            EventType::UpdateBitCrusherAmount(1),
+            EventType::UpdateSaturationEnabled(true),
+            EventType::UpdateSaturationMode(SharedString::from("Saw")),
+            EventType::UpdateSaturationAmount(0.5),
+            EventType::UpdateMakeupGain(1),
+            EventType::UpdateCompressorActive(true),
+            EventType::UpdateCompressorThreshold(0.5),
+            EventType::UpdateCompressorRatio(0.5),
+            EventType::UpdateWaveFolderActive(true),
+            EventType::UpdateWaveFolderThreshold(0.5),
+            EventType::UpdateWaveFolderRatio(0.5),
+            EventType::UpdateLimiterActive(true),
+            EventType::UpdateLimiterThreshold(0.5),
+            EventType::UpdateClipperActive(true),
+            EventType::UpdateClipperThreshold(0.5),
+            EventType::ResyncOscillators,
+            EventType::ResyncOscillatorLFOs,
+            EventType::UpdateNumberOfVoices(1),
+            EventType::UpdateVoiceStealingMode(SharedString::from("Saw")),
+            EventType::UpdateVoiceMode(SharedString::from("Saw")),
+            EventType::UpdateNotePriority(SharedString::from("Saw")),
+            EventType::UpdateLegatoEnabled(true),
+            EventType::UpdateGlideTime(1),
+            EventType::UpdateGlideCurve(SharedString::from("Saw")),
+            EventType::UpdateGlideMode(SharedString::from("Saw")),
+            EventType::ArpeggiatorActive(true),
+            EventType::ArpeggiatorAddNote(1),
+            EventType::ArpeggiatorRemoveNote(1),
+            EventType::ArpeggiatorRandomEnabled(true),
+            EventType::ArpeggiatorGlideEnabled(true),
+        ]
+    }
+
+    #[test]
+    fn every_event_reads_back_the_way_it_prints() {
+        for event in get_every_patch_event() {
+            let line = format!("{event:?}");
+            assert_eq!(get_event_from_patch_line(&line).unwrap(), event, "{line}");
+        }
+    }
+
+    #[test]
+    fn quoted_text_reads_back_with_its_escapes() {
+        let event = EventType::LoadWavetable(SharedString::from(r#"tables\"Bright, wide".wav"#), 0);
+        let line = format!("{event:?}");
+
+        assert_eq!(get_event_from_patch_line(&line).unwrap(), event);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "UpdateOscillatorLevel(0.5, 0",
+            "UpdateOscillatorLevel(0.5)",
+            "UpdateOscillatorLevel(0.5, 0, 1)",
+            "UpdateOscillatorLevel(loud, 0)",
+            "UpdateOscillatorLevel(NaN, 0)",
+            "UpdateOscillatorShape(Saw, 0)",
+            "UpdateOscillatorSpecificParameters(0.5, 0)",
+            "UpdateOscillatorLevel(0.5, 4)",
+            "UpdateOscillatorLevel(0.5, -1)",
+            "UpdateFilterCutoffValue(800, 2)",
+            "UpdateAutoPanEnabled(yes)",
+            "MidiNoteOn(60, 127)",
+            "StartRecording",
+        ] {
+            assert!(get_event_from_patch_line(line).is_err(), "{line}");
+        }
+    }
+
+    #[test]
+    fn patch_file_skips_comments_and_blank_lines() {
+        let patch_path = get_patch_file_path(
+            "# A bright lead\n\nUpdateOscillatorShape(\"Saw\", 0)\n  UpdateFilterCutoffValue(800, 1)\n",
+            "comments.patch",
+        );
+        let events = get_events_from_patch_file(&patch_path);
+        fs::remove_file(&patch_path).unwrap();
+
+        assert_eq!(
+            events.unwrap(),
+            [
+                EventType::UpdateOscillatorShape(SharedString::from("Saw"), 0),
+                EventType::UpdateFilterCutoffValue(800, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn patch_file_errors_name_the_line() {
+        let patch_path = get_patch_file_path(
+            "# A bright lead\nUpdateOscillatorShape(\"Saw\", 0)\nUpdateOscillatorLevel(0.5, 7)\n",
+            "bad_oscillator.patch",
+        );
+        let error = get_events_from_patch_file(&patch_path).unwrap_err();
+        fs::remove_file(&patch_path).unwrap();
+
+        assert!(error.to_string().starts_with("Patch line 3:"), "{error}");
+    }
+}
@@ -9,6 +9,18 @@ pub const DEFAULT_OUTPUT_PAN_VALUE: f32 = 1.0;
 // Arpeggiator Constants
 pub const DEFAULT_SEQUENCER_NOTE: u16 = 128;
 
+// Tuning Constants
+pub const DEFAULT_MASTER_TUNE: f32 = 440.0;
+pub const A4_MIDI_NOTE: f32 = 69.0;
+pub const MINIMUM_MASTER_TUNE: f32 = 415.0;
+pub const MAXIMUM_MASTER_TUNE: f32 = 466.0;
+
+// Filter Modulation Constants
+pub const MAXIMUM_MIDI_VELOCITY: f32 = 127.0;
+pub const MAXIMUM_FILTER_ENVELOPE_OCTAVES: f32 = 8.0;
+pub const FILTER_VELOCITY_RANGE_OCTAVES: f32 = 4.0;
+pub const FILTER_KEY_TRACKING_CENTER_NOTE: f32 = 60.0;
+
 // Effects Constants
 pub const DEFAULT_COMPRESSOR_THRESHOLD: f32 = 0.0;
 pub const DEFAULT_BIT_CRUSHER_DEPTH: u32 = 8;
@@ -20,6 +32,7 @@ pub const DEFAULT_LFO_FREQUENCY: f32 = 1.0;
 pub const DEFAULT_OSC_MOD_FREQUENCY: f32 = 0.01;
 pub const DEFAULT_OSC_MOD_CENTER_VALUE: f32 = 1.0;
 pub const DEFAULT_COMPRESSOR_RATIO: f32 = 0.5;
+pub const PHASER_DELAY_BUFFER_LENGTH: usize = 128;
 
 // LFO Constants
 pub const LFO_INDEX_FOR_AUTO_PAN: usize = 0;
@@ -1,13 +1,12 @@
 use crate::synth::LFOParameters;
 use crate::synth::lfo::LFO;
-use std::sync::MutexGuard;
 
 const PHASER_MAX_WIDTH_VALUE: usize = 126;
 
 pub fn get_phased_sample(
     lfo: &mut LFO,
     phaser: &mut LFOParameters,
-    delay_buffer: &mut MutexGuard<Vec<(f32, f32)>>,
+    delay_buffer: &mut Vec<(f32, f32)>,
     left_sample: f32,
     right_sample: f32,
 ) -> (f32, f32) {
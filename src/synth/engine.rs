@@ -0,0 +1,1352 @@
+use crate::events::EventType;
+use crate::synth::arpeggiator::{Arpeggiator, ArpeggiatorType, FIRST_REST_NOTE};
+use crate::synth::constants::*;
+use crate::synth::dynamics::{Dynamics, get_f32_sample_from_dbfs};
+use crate::synth::envelope::{ADSRState, GateState};
+use crate::synth::filter::FilterRouting;
+use crate::synth::lfo::LFO;
+use crate::synth::oscillators::sine::Sine;
+use crate::synth::oscillators::{GenerateSamples, ShapeData, ShapeParameter, WaveShape};
+use crate::synth::oscillators::{additive, four_operator_fm, sampler};
+use crate::synth::recorder::RecordingTap;
+use crate::synth::voices::{ARPEGGIATOR_VOICE_INDEX, Voice, Voices};
+use crate::synth::{
+    AmpMode, DynamicsParameters, EffectsParameters, LFOParameters, MidiState, SynthParameters,
+    effects, filter, glide, oscillators, saturation, voices,
+};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+pub struct Engine {
+    sample_rate: f32,
+    seed_generator: SmallRng,
+    voices: Voices,
+    lfos: Vec<LFO>,
+    dynamics: Dynamics,
+    parameters: SynthParameters,
+    phaser_buffer: Vec<(f32, f32)>,
+    recording_tap: Option<RecordingTap>,
+}
+
+impl Engine {
+    // The same seed renders the same noise and random phases every time
+    pub fn new(seed: u64, sample_rate: f32) -> Self {
+        let mut seed_generator = SmallRng::seed_from_u64(seed);
+
+        // Set up the voices, each with its own oscillators, envelope and filter
+        let voices = Voices::new(seed_generator.random(), sample_rate, DEFAULT_SEQUENCER_NOTE);
+
+        let lfos = vec![
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+            LFO::new(Box::new(Sine::new(sample_rate))),
+        ];
+
+        let dynamics_processor = Dynamics::new();
+
+        let auto_pan = LFOParameters {
+            center_value: DEFAULT_AUTO_PAN_CENTER_VALUE,
+            frequency: DEFAULT_LFO_FREQUENCY,
+            ..Default::default()
+        };
+
+        let tremolo = LFOParameters {
+            center_value: DEFAULT_CENTER_VALUE,
+            frequency: DEFAULT_LFO_FREQUENCY,
+            ..Default::default()
+        };
+
+        let filter_mod = LFOParameters {
+            center_value: DEFAULT_CENTER_VALUE,
+            frequency: DEFAULT_LFO_FREQUENCY,
+            ..Default::default()
+        };
+
+        let phaser = LFOParameters {
+            center_value: DEFAULT_PHASER_CENTER_VALUE,
+            frequency: DEFAULT_LFO_FREQUENCY,
+            width: DEFAULT_PHASER_WIDTH,
+            ..Default::default()
+        };
+
+        let sub_osc_mod = LFOParameters {
+            center_value: DEFAULT_OSC_MOD_CENTER_VALUE,
+            frequency: DEFAULT_OSC_MOD_FREQUENCY,
+            ..Default::default()
+        };
+
+        let osc1_mod = LFOParameters {
+            center_value: DEFAULT_OSC_MOD_CENTER_VALUE,
+            frequency: DEFAULT_OSC_MOD_FREQUENCY,
+            ..Default::default()
+        };
+
+        let osc2_mod = LFOParameters {
+            center_value: DEFAULT_OSC_MOD_CENTER_VALUE,
+            frequency: DEFAULT_OSC_MOD_FREQUENCY,
+            ..Default::default()
+        };
+
+        let osc3_mod = LFOParameters {
+            center_value: DEFAULT_OSC_MOD_CENTER_VALUE,
+            frequency: DEFAULT_OSC_MOD_FREQUENCY,
+            ..Default::default()
+        };
+
+        let oscillator_mod_lfos = vec![sub_osc_mod, osc1_mod, osc2_mod, osc3_mod];
+
+        let dynamics = DynamicsParameters {
+            compressor_ratio: DEFAULT_COMPRESSOR_RATIO,
+            compressor_threshold: DEFAULT_COMPRESSOR_THRESHOLD,
+            ..Default::default()
+        };
+
+        let effects = EffectsParameters {
+            phaser,
+            bitcrusher_depth: DEFAULT_BIT_CRUSHER_DEPTH,
+            saturation_amount: DEFAULT_WAVE_SHAPER_AMOUNT,
+            ..Default::default()
+        };
+
+        let arpeggiator = Arpeggiator::new(vec![DEFAULT_SEQUENCER_NOTE]);
+
+        let parameters = SynthParameters {
+            amp_mode: AmpMode::Envelope,
+            output_level: OUTPUT_LEVEL,
+            manual_pan_value: DEFAULT_OUTPUT_PAN_VALUE,
+            auto_pan,
+            tremolo,
+            filter_mod,
+            filter_mod_shape: Default::default(),
+            filter_envelope_amount: 0.0,
+            filter_key_tracking: 0.0,
+            filter_velocity_amount: 0.0,
+            filter_routing: Default::default(),
+            oscillator_mod_lfos,
+            output_level_constant: true,
+            dynamics,
+            effects,
+            arpeggiator,
+            arpeggiator_type: Default::default(),
+            arpeggiator_is_active: false,
+            arpeggiator_glide_is_enabled: false,
+            master_tune: DEFAULT_MASTER_TUNE,
+            audio_output_channel_indexes: (
+                DEFAULT_AUDIO_OUTPUT_LEFT_FRAME_INDEX,
+                Some(DEFAULT_AUDIO_OUTPUT_RIGHT_FRAME_INDEX),
+            ),
+        };
+
+        Self {
+            sample_rate,
+            seed_generator,
+            voices,
+            lfos,
+            dynamics: dynamics_processor,
+            parameters,
+            phaser_buffer: vec![(0.0, 0.0); PHASER_DELAY_BUFFER_LENGTH],
+            recording_tap: None,
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &EventType) {
+        match *event {
+            EventType::UpdateOscillatorShape(ref shape, oscillator) => {
+                let wave_shape = oscillators::get_wave_shape_from_shape_name(shape.as_str());
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_type(wave_shape.clone(), oscillator);
+                });
+            }
+            EventType::UpdateOscillatorQuality(ref quality_name) => {
+                let quality =
+                    oscillators::get_oscillator_quality_from_quality_name(quality_name.as_str());
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_quality(quality);
+                });
+            }
+            EventType::UpdateOscillatorTuning(interval, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_interval(interval, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorOctave(octave, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_octave(octave, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorFineTune(cents, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_fine_tune(cents, oscillator);
+                });
+            }
+            EventType::UpdateSubOctave(octave) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_sub_octave(octave);
+                });
+            }
+            EventType::UpdateMasterTune(frequency) => {
+                self.parameters.master_tune =
+                    frequency.clamp(MINIMUM_MASTER_TUNE, MAXIMUM_MASTER_TUNE);
+            }
+            EventType::UpdateOscillatorLevel(level, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_level(level, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorSpecificParameters(parameters, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_specific_parameters(parameters, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorNumberOfVoices(number_of_voices, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_parameter(
+                        ShapeParameter::SuperSawVoices(number_of_voices),
+                        oscillator,
+                    );
+                });
+            }
+            EventType::UpdateOscillatorStereoSpread(stereo_spread, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_parameter(
+                        ShapeParameter::SuperSawStereoSpread(stereo_spread),
+                        oscillator,
+                    );
+                });
+            }
+            EventType::UpdateOscillatorSyncSource(ref source_name, oscillator) => {
+                let source = oscillators::get_oscillator_index_from_source_name(source_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_sync_source(source, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorRingModSource(ref source_name, oscillator) => {
+                let source = oscillators::get_oscillator_index_from_source_name(source_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_ring_modulation_source(source, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorFMSource(ref source_name, oscillator) => {
+                let source = oscillators::get_oscillator_index_from_source_name(source_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_fm_source(source, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorFMMode(ref mode_name, oscillator) => {
+                let mode = oscillators::get_fm_mode_from_mode_name(mode_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_fm_mode(mode, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorFMAmount(amount, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_fm_amount(amount, oscillator);
+                });
+            }
+            EventType::UpdateFMAlgorithm(algorithm, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators
+                        .set_shape_parameter(ShapeParameter::FMAlgorithm(algorithm), oscillator);
+                });
+            }
+            EventType::UpdateFMOperatorParameter(
+                ref parameter_name,
+                value,
+                operator,
+                oscillator,
+            ) => {
+                let parameter =
+                    four_operator_fm::get_operator_parameter_from_parameter_name(parameter_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_parameter(
+                        ShapeParameter::FMOperator(operator, parameter, value),
+                        oscillator,
+                    );
+                });
+            }
+            EventType::UpdateAdditivePreset(ref preset_name, oscillator) => {
+                let preset = additive::get_additive_preset_from_preset_name(preset_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators
+                        .set_shape_parameter(ShapeParameter::AdditivePreset(preset), oscillator);
+                });
+            }
+            EventType::UpdateAdditiveStretch(stretch, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators
+                        .set_shape_parameter(ShapeParameter::AdditiveStretch(stretch), oscillator);
+                });
+            }
+            // The root key comes from the note table, which is tuned to A4 = 440 Hz like the notes
+            // played before the master tune, so retuning the synth retunes the sample with it
+            EventType::UpdateSamplerRootKey(root_key, oscillator) => {
+                let root_frequency = self
+                    .parameters
+                    .arpeggiator
+                    .get_frequency_from_midi_note(root_key.max(0) as u16);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_parameter(
+                        ShapeParameter::SamplerRootFrequency(root_frequency),
+                        oscillator,
+                    );
+                });
+            }
+            EventType::UpdateSamplerLoopMode(ref mode_name, oscillator) => {
+                let loop_mode = sampler::get_loop_mode_from_mode_name(mode_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_parameter(
+                        ShapeParameter::SamplerLoopMode(loop_mode),
+                        oscillator,
+                    );
+                });
+            }
+            EventType::UpdateSamplerInterpolation(ref interpolation_name, oscillator) => {
+                let interpolation =
+                    sampler::get_sample_interpolation_from_interpolation_name(interpolation_name);
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_shape_parameter(
+                        ShapeParameter::SamplerInterpolation(interpolation),
+                        oscillator,
+                    );
+                });
+            }
+            EventType::UpdateOscillatorDriveAmount(amount, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_drive_amount(amount, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorModFreq(speed, oscillator) => {
+                self.parameters.oscillator_mod_lfos[oscillator as usize].frequency = speed;
+            }
+            EventType::UpdateOscillatorModAmount(amount, oscillator) => {
+                self.parameters.oscillator_mod_lfos[oscillator as usize].width = amount;
+            }
+            EventType::UpdateOscillatorUnisonVoices(number_of_voices, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_unison_voices(number_of_voices, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorUnisonDetune(detune_cents, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_unison_detune(detune_cents, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorUnisonWidth(width, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_unison_width(width, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorUnisonRandomPhase(is_active, oscillator) => {
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_unison_phase_randomized(is_active, oscillator);
+                });
+            }
+            EventType::UpdateOscillatorFilterSend(ref send_name, oscillator) => {
+                let filter_send = filter::get_filter_send_from_send_name(send_name.as_str());
+                self.voices.oscillators_mut().for_each(|oscillators| {
+                    oscillators.set_oscillator_filter_send(filter_send, oscillator);
+                });
+            }
+            EventType::UpdateOutputLevel(level) => {
+                self.parameters.output_level = level as f32;
+            }
+            EventType::UpdateOutputLevelConstant(is_active) => {
+                self.parameters.output_level_constant = is_active;
+            }
+            EventType::UpdateOutputPan(pan) => {
+                self.parameters.manual_pan_value = pan;
+            }
+            EventType::UpdateEnvelopeAttack(milliseconds) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_attack_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateEnvelopeDecay(milliseconds) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_decay_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateEnvelopeRelease(milliseconds) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_release_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateADSRNoteLength(milliseconds) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_sustain_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateEnvelopeSustainLevel(level) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_sustain_level_below_output_level_in_dbfs(level as f32);
+                });
+            }
+            EventType::UpdateAmpModeEnvelopeEnabled(is_enabled) => {
+                if is_enabled {
+                    self.parameters.amp_mode = AmpMode::Envelope;
+                } else {
+                    self.parameters.amp_mode = AmpMode::Gate;
+                }
+            }
+            EventType::UpdateGateDutyCycle(duty_cycle) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_gate_duty_cycle(duty_cycle);
+                });
+            }
+            EventType::UpdateGateNoteLength(note_length) => {
+                self.voices.envelopes_mut().for_each(|envelope| {
+                    envelope.set_gate_note_length(note_length.unsigned_abs());
+                });
+            }
+            EventType::UpdateFilterCutoffValue(cutoff, filter_index) => {
+                self.voices.filters_mut(filter_index).for_each(|filter| {
+                    filter.set_cutoff_frequency(cutoff as f32);
+                });
+            }
+            EventType::UpdateFilterResonanceValue(level, filter_index) => {
+                self.voices.filters_mut(filter_index).for_each(|filter| {
+                    filter.set_resonance(level);
+                });
+            }
+            EventType::UpdateFilterNumberOfPoles(number_of_poles, filter_index) => {
+                self.voices.filters_mut(filter_index).for_each(|filter| {
+                    filter.set_number_of_poles(number_of_poles);
+                });
+            }
+            EventType::UpdateFilterType(ref filter_type, filter_index) => {
+                let filter_type = filter::get_filter_type_from_type_name(filter_type.as_str());
+                self.voices.filters_mut(filter_index).for_each(|filter| {
+                    filter.set_filter_type(filter_type);
+                });
+            }
+            EventType::UpdateFilterModel(ref model, filter_index) => {
+                let model = filter::get_filter_model_from_model_name(model.as_str());
+                self.voices.filters_mut(filter_index).for_each(|filter| {
+                    filter.set_filter_model(model);
+                });
+            }
+            EventType::UpdateFilterDrive(drive, filter_index) => {
+                self.voices.filters_mut(filter_index).for_each(|filter| {
+                    filter.set_drive(drive);
+                });
+            }
+            EventType::UpdateFilterRouting(ref routing) => {
+                self.parameters.filter_routing =
+                    filter::get_filter_routing_from_routing_name(routing.as_str());
+            }
+            EventType::UpdateFilterEnvelopeAttack(milliseconds) => {
+                self.voices.filter_envelopes_mut().for_each(|envelope| {
+                    envelope.set_attack_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateFilterEnvelopeDecay(milliseconds) => {
+                self.voices.filter_envelopes_mut().for_each(|envelope| {
+                    envelope.set_decay_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateFilterEnvelopeSustain(proportion) => {
+                self.voices.filter_envelopes_mut().for_each(|envelope| {
+                    envelope.set_sustain_proportion(proportion);
+                });
+            }
+            EventType::UpdateFilterEnvelopeRelease(milliseconds) => {
+                self.voices.filter_envelopes_mut().for_each(|envelope| {
+                    envelope.set_release_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateFilterEnvelopeAmount(octaves) => {
+                self.parameters.filter_envelope_amount = octaves.clamp(
+                    -MAXIMUM_FILTER_ENVELOPE_OCTAVES,
+                    MAXIMUM_FILTER_ENVELOPE_OCTAVES,
+                );
+            }
+            EventType::UpdateFilterKeyTracking(amount) => {
+                self.parameters.filter_key_tracking = amount.clamp(0.0, 1.0);
+            }
+            EventType::UpdateFilterVelocityAmount(amount) => {
+                self.parameters.filter_velocity_amount = amount.clamp(0.0, 1.0);
+            }
+            EventType::ResyncOscillators => {
+                self.voices
+                    .oscillators_mut()
+                    .for_each(|oscillators| oscillators.reset());
+            }
+            EventType::ResyncOscillatorLFOs => {
+                self.lfos[LFO_INDEX_FOR_SUB_OSCILLATOR_MOD].reset();
+                self.lfos[LFO_INDEX_FOR_OSCILLATOR1_MOD].reset();
+                self.lfos[LFO_INDEX_FOR_OSCILLATOR2_MOD].reset();
+                self.lfos[LFO_INDEX_FOR_OSCILLATOR3_MOD].reset();
+            }
+            EventType::UpdateAutoPanEnabled(is_enabled) => {
+                self.parameters.auto_pan.is_enabled = is_enabled;
+            }
+            EventType::UpdateAutoPanSpeed(speed_hz) => {
+                self.parameters.auto_pan.frequency = speed_hz;
+            }
+            EventType::UpdateAutoPanWidth(width) => {
+                self.parameters.auto_pan.width = width;
+            }
+            EventType::UpdateTremoloEnabled(is_enabled) => {
+                self.parameters.tremolo.is_enabled = is_enabled;
+            }
+            EventType::UpdateTremoloSpeed(speed_hz) => {
+                self.parameters.tremolo.frequency = speed_hz;
+            }
+            EventType::UpdateTremoloDepth(depth) => {
+                self.parameters.tremolo.width = depth;
+                self.parameters.tremolo.center_value = 1.0 - (depth / 2.0);
+            }
+            EventType::UpdateFilterModEnabled(is_enabled) => {
+                self.parameters.filter_mod.is_enabled = is_enabled;
+            }
+            EventType::UpdateFilterModSpeed(speed_hz) => {
+                self.parameters.filter_mod.frequency = speed_hz;
+            }
+            EventType::UpdateFilterModAmount(amount) => {
+                self.parameters.filter_mod.width = amount;
+                self.parameters.filter_mod.center_value = 1.0 - (amount / 2.0);
+            }
+            EventType::UpdateFilterModShape(ref shape) => {
+                let filter_mod_shape = oscillators::get_wave_shape_from_shape_name(shape.as_str());
+                let filter_mod_lfo = oscillators::get_oscillator_for_wave_shape(
+                    &filter_mod_shape,
+                    &mut self.seed_generator,
+                    self.sample_rate,
+                );
+
+                self.lfos[LFO_INDEX_FOR_FILTER_MOD] = LFO::new(filter_mod_lfo);
+                self.parameters.filter_mod_shape = filter_mod_shape;
+            }
+            EventType::UpdatePhaserEnabled(is_enabled) => {
+                self.parameters.effects.phaser.is_enabled = is_enabled;
+            }
+            EventType::UpdatePhaserSpeed(speed_hz) => {
+                self.parameters.effects.phaser.frequency = speed_hz;
+            }
+            EventType::UpdatePhaserAmount(amount) => {
+                self.parameters.effects.phaser.width = amount;
+                self.parameters.effects.phaser.center_value =
+                    effects::get_phaser_lfo_center_value_from_amount(amount);
+            }
+            EventType::UpdateBitCrusherEnabled(is_enabled) => {
+                self.parameters.effects.bitcrusher_is_enabled = is_enabled;
+            }
+            EventType::UpdateBitCrusherAmount(depth) => {
+                self.parameters.effects.bitcrusher_depth = depth as u32;
+            }
+            EventType::UpdateSaturationEnabled(is_enabled) => {
+                self.parameters.effects.saturation_is_enabled = is_enabled;
+            }
+            EventType::UpdateSaturationMode(ref mode_name) => {
+                let saturation_mode =
+                    saturation::get_saturation_mode_from_mode_name(mode_name.as_str());
+                self.parameters.effects.saturation_mode = saturation_mode;
+            }
+            EventType::UpdateMakeupGain(gain) => {
+                self.parameters.effects.saturation_makeup_gain =
+                    get_f32_sample_from_dbfs(gain as f32);
+            }
+            EventType::UpdateSaturationAmount(amount) => {
+                self.parameters.effects.saturation_amount = amount;
+            }
+            EventType::UpdateCompressorActive(is_active) => {
+                self.parameters.dynamics.compressor_enabled = is_active;
+            }
+            EventType::UpdateCompressorThreshold(threshold) => {
+                self.parameters.dynamics.compressor_threshold = threshold;
+            }
+            EventType::UpdateCompressorRatio(ratio) => {
+                self.parameters.dynamics.compressor_ratio = ratio;
+            }
+            EventType::UpdateWaveFolderActive(is_active) => {
+                self.parameters.dynamics.wave_folder_enabled = is_active;
+            }
+            EventType::UpdateWaveFolderThreshold(threshold) => {
+                self.parameters.dynamics.wave_folder_threshold = threshold;
+            }
+            EventType::UpdateWaveFolderRatio(ratio) => {
+                self.parameters.dynamics.wave_folder_ratio = ratio;
+            }
+            EventType::UpdateLimiterActive(is_active) => {
+                self.parameters.dynamics.limiter_enabled = is_active;
+            }
+            EventType::UpdateLimiterThreshold(threshold) => {
+                self.parameters.dynamics.limiter_threshold = threshold;
+            }
+            EventType::UpdateClipperActive(is_active) => {
+                self.parameters.dynamics.clipper_enabled = is_active;
+            }
+            EventType::UpdateClipperThreshold(threshold) => {
+                self.parameters.dynamics.clipper_threshold = threshold;
+            }
+            EventType::ArpeggiatorActive(is_active) => {
+                {
+                    self.parameters.arpeggiator_is_active = is_active;
+                }
+                self.voices.release_all_notes();
+            }
+            EventType::ArpeggiatorAddNote(note_number) => {
+                self.parameters.arpeggiator.add_note(note_number as u16);
+            }
+            EventType::ArpeggiatorRemoveNote(note_number) => {
+                self.parameters.arpeggiator.remove_note(note_number as u16);
+            }
+            EventType::ArpeggiatorRandomEnabled(is_active) => {
+                if is_active {
+                    self.parameters.arpeggiator_type = ArpeggiatorType::Randomize;
+                } else {
+                    self.parameters.arpeggiator_type = ArpeggiatorType::NoteOrder;
+                }
+            }
+            EventType::UpdateNumberOfVoices(number_of_voices) => {
+                self.voices.set_number_of_voices(number_of_voices);
+            }
+            EventType::UpdateVoiceStealingMode(ref mode_name) => {
+                let stealing_mode = voices::get_stealing_mode_from_mode_name(mode_name.as_str());
+                self.voices.set_stealing_mode(stealing_mode);
+            }
+            EventType::UpdateVoiceMode(ref mode_name) => {
+                let voice_mode = voices::get_voice_mode_from_mode_name(mode_name.as_str());
+                self.voices.set_voice_mode(voice_mode);
+            }
+            EventType::UpdateNotePriority(ref priority_name) => {
+                let note_priority =
+                    voices::get_note_priority_from_priority_name(priority_name.as_str());
+                self.voices.set_note_priority(note_priority);
+            }
+            EventType::UpdateLegatoEnabled(is_enabled) => {
+                self.voices.set_legato_enabled(is_enabled);
+            }
+            EventType::UpdateGlideTime(milliseconds) => {
+                self.voices.glides_mut().for_each(|glide| {
+                    glide.set_glide_time_milliseconds(milliseconds.unsigned_abs());
+                });
+            }
+            EventType::UpdateGlideCurve(ref curve_name) => {
+                let glide_curve = glide::get_glide_curve_from_curve_name(curve_name.as_str());
+                self.voices
+                    .glides_mut()
+                    .for_each(|glide| glide.set_curve(glide_curve));
+            }
+            EventType::UpdateGlideMode(ref mode_name) => {
+                let glide_mode = glide::get_glide_mode_from_mode_name(mode_name.as_str());
+                self.voices
+                    .glides_mut()
+                    .for_each(|glide| glide.set_mode(glide_mode));
+            }
+            EventType::ArpeggiatorGlideEnabled(is_enabled) => {
+                self.parameters.arpeggiator_glide_is_enabled = is_enabled;
+            }
+            EventType::MidiNoteOn(note_number, velocity)
+                if !self.parameters.arpeggiator_is_active =>
+            {
+                self.voices
+                    .note_on(note_number as u16, velocity as f32 / MAXIMUM_MIDI_VELOCITY);
+            }
+            EventType::MidiNoteOff(note_number) if !self.parameters.arpeggiator_is_active => {
+                self.voices.note_off(note_number as u16);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn reset_audio_output_channels(&mut self) {
+        self.parameters.audio_output_channel_indexes = (
+            DEFAULT_AUDIO_OUTPUT_LEFT_FRAME_INDEX,
+            Some(DEFAULT_AUDIO_OUTPUT_RIGHT_FRAME_INDEX),
+        );
+    }
+
+    pub fn set_audio_output_channels(&mut self, left: &str, right: &str) {
+        self.parameters.audio_output_channel_indexes =
+            get_channel_frame_indexes_from_channel_names(left, right);
+    }
+
+    // Leaves the previous waves in `waves` so they can be dropped away from the audio thread
+    pub fn swap_oscillator_waves(
+        &mut self,
+        waves: &mut [Box<dyn GenerateSamples + Send + Sync>],
+        wave_shape: WaveShape,
+        oscillator: i32,
+    ) {
+        self.voices
+            .oscillators_mut()
+            .zip(waves.iter_mut())
+            .for_each(|(oscillators, wave)| {
+                oscillators.swap_oscillator_wave(wave, wave_shape.clone(), oscillator);
+            });
+    }
+
+    // Leaves the previous waves in `waves` so they can be dropped away from the audio thread
+    pub fn swap_unison_voices(
+        &mut self,
+        waves: &mut [Box<dyn GenerateSamples + Send + Sync>],
+        number_of_voices: i32,
+        oscillator: i32,
+    ) {
+        self.voices
+            .oscillators_mut()
+            .zip(waves.iter_mut())
+            .for_each(|(oscillators, wave)| {
+                oscillators.swap_unison_voices(wave, number_of_voices, oscillator);
+            });
+    }
+
+    // Leaves the previous tables or samples in `shape_data` so they can be dropped away from the
+    // audio thread
+    pub fn swap_shape_data(&mut self, shape_data: &mut [ShapeData], oscillator: i32) {
+        self.voices
+            .oscillators_mut()
+            .zip(shape_data.iter_mut())
+            .for_each(|(oscillators, shape_data)| {
+                oscillators.swap_shape_data(shape_data, oscillator);
+            });
+    }
+
+    pub fn replace_filter_mod_wave(
+        &mut self,
+        wave: Box<dyn GenerateSamples + Send + Sync>,
+        wave_shape: WaveShape,
+    ) -> Box<dyn GenerateSamples + Send + Sync> {
+        self.parameters.filter_mod_shape = wave_shape;
+        self.lfos[LFO_INDEX_FOR_FILTER_MOD].replace_oscillator(wave)
+    }
+
+    pub fn start_recording(&mut self, recording_tap: RecordingTap) -> Option<RecordingTap> {
+        self.recording_tap.replace(recording_tap)
+    }
+
+    // Hands the tap back so it is dropped off the audio thread
+    pub fn stop_recording(&mut self) -> Option<RecordingTap> {
+        self.recording_tap.take()
+    }
+
+    pub fn render(&mut self, out: &mut [f32], channels: usize) {
+        let left_channel_index = self.parameters.audio_output_channel_indexes.0;
+        let right_channel_index = self.parameters.audio_output_channel_indexes.1;
+
+        // Start the processing of individual frames
+        for frame in out.chunks_mut(channels) {
+            let (left_sample, right_sample) = self.next_frame();
+
+            let mut recorded_frame = (0.0, 0.0);
+
+            if let Some(left_output) = frame.get_mut(left_channel_index) {
+                *left_output = left_sample;
+                recorded_frame.0 = left_sample;
+            }
+
+            if let Some(right_index) = right_channel_index
+                && channels > 1
+                && let Some(right_output) = frame.get_mut(right_index)
+            {
+                *right_output = right_sample;
+                recorded_frame.1 = right_sample;
+            }
+
+            if let Some(recording_tap) = self.recording_tap.as_mut() {
+                recording_tap.record_frame(recorded_frame);
+            }
+        }
+    }
+
+    fn next_frame(&mut self) -> (f32, f32) {
+        let sub_oscillator_modulation = get_oscillator_mod_value(
+            &mut self.lfos[LFO_INDEX_FOR_SUB_OSCILLATOR_MOD],
+            self.parameters.oscillator_mod_lfos[OSC_MOD_LFO_INDEX_FOR_SUB],
+        );
+
+        let oscillator1_modulation = get_oscillator_mod_value(
+            &mut self.lfos[LFO_INDEX_FOR_OSCILLATOR1_MOD],
+            self.parameters.oscillator_mod_lfos[OSC_MOD_LFO_INDEX_FOR_OSC1],
+        );
+
+        let oscillator2_modulation = get_oscillator_mod_value(
+            &mut self.lfos[LFO_INDEX_FOR_OSCILLATOR2_MOD],
+            self.parameters.oscillator_mod_lfos[OSC_MOD_LFO_INDEX_FOR_OSC2],
+        );
+
+        let oscillator3_modulation = get_oscillator_mod_value(
+            &mut self.lfos[LFO_INDEX_FOR_OSCILLATOR3_MOD],
+            self.parameters.oscillator_mod_lfos[OSC_MOD_LFO_INDEX_FOR_OSC3],
+        );
+
+        let oscillator_modulations = [
+            sub_oscillator_modulation,
+            oscillator1_modulation,
+            oscillator2_modulation,
+            oscillator3_modulation,
+        ];
+
+        let filter_mod_value = get_filter_mod_value(
+            &mut self.lfos[LFO_INDEX_FOR_FILTER_MOD],
+            &mut self.parameters,
+        );
+
+        let mut left_sample = 0.0;
+        let mut right_sample = 0.0;
+
+        for (voice_index, voice) in self.voices.iter_mut().enumerate() {
+            let is_arpeggiator_voice =
+                self.parameters.arpeggiator_is_active && voice_index == ARPEGGIATOR_VOICE_INDEX;
+
+            if !voice.is_active() && !is_arpeggiator_voice {
+                continue;
+            }
+
+            let (left_voice_sample, right_voice_sample) = get_voice_sample(
+                voice,
+                &mut self.parameters,
+                oscillator_modulations,
+                filter_mod_value,
+                is_arpeggiator_voice,
+            );
+            left_sample += left_voice_sample;
+            right_sample += right_voice_sample;
+        }
+
+        if self.parameters.auto_pan.is_enabled {
+            (left_sample, right_sample) = effects::get_auto_pan_value(
+                &mut self.lfos[LFO_INDEX_FOR_AUTO_PAN],
+                &mut self.parameters.auto_pan,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.effects.phaser.is_enabled {
+            (left_sample, right_sample) = effects::get_phased_sample(
+                &mut self.lfos[LFO_INDEX_FOR_PHASE_DELAY],
+                &mut self.parameters.effects.phaser,
+                &mut self.phaser_buffer,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.tremolo.is_enabled {
+            (left_sample, right_sample) = effects::get_tremolo_value(
+                &mut self.lfos[LFO_INDEX_FOR_TREMOLO],
+                &mut self.parameters.tremolo,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.effects.bitcrusher_is_enabled {
+            (left_sample, right_sample) = effects::get_bitcrush_sample(
+                self.parameters.effects.bitcrusher_depth,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.effects.saturation_is_enabled {
+            (left_sample, right_sample) = saturation::get_saturated_samples(
+                self.parameters.effects.saturation_mode,
+                self.parameters.effects.saturation_amount,
+                left_sample,
+                right_sample,
+            );
+            left_sample *= self.parameters.effects.saturation_makeup_gain;
+            right_sample *= self.parameters.effects.saturation_makeup_gain;
+        }
+
+        if self.parameters.dynamics.wave_folder_enabled {
+            (left_sample, right_sample) = get_wavefolded_samples(
+                &mut self.parameters,
+                &self.dynamics,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.dynamics.compressor_enabled {
+            (left_sample, right_sample) = get_compressed_samples(
+                &mut self.parameters,
+                &self.dynamics,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.dynamics.limiter_enabled {
+            (left_sample, right_sample) = get_limited_samples(
+                &mut self.parameters,
+                &self.dynamics,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        if self.parameters.dynamics.clipper_enabled {
+            (left_sample, right_sample) = get_clipped_samples(
+                &mut self.parameters,
+                &self.dynamics,
+                left_sample,
+                right_sample,
+            );
+        }
+
+        let (left_pan_adjustment, right_pan_adjustment) =
+            effects::get_sample_adjustment_for_pan_value(self.parameters.manual_pan_value);
+
+        (
+            left_sample * left_pan_adjustment,
+            right_sample * right_pan_adjustment,
+        )
+    }
+}
+
+fn get_voice_sample(
+    voice: &mut Voice,
+    parameters: &mut SynthParameters,
+    oscillator_modulations: [Option<f32>; 4],
+    filter_mod_value: Option<f32>,
+    is_arpeggiator_voice: bool,
+) -> (f32, f32) {
+    let glide_ratio = voice.glide.next_frequency_ratio();
+    let oscillators = &mut voice.oscillators;
+
+    let sub_oscillator_frequency = get_frequency_from_midi_note_and_osc_interval(
+        voice.midi_note,
+        oscillators.get_oscillator_interval(0),
+        oscillators.get_oscillator_fine_tune(0),
+        parameters.master_tune,
+    ) * glide_ratio;
+
+    let oscillator1_frequency = get_frequency_from_midi_note_and_osc_interval(
+        voice.midi_note,
+        oscillators.get_oscillator_interval(1),
+        oscillators.get_oscillator_fine_tune(1),
+        parameters.master_tune,
+    ) * glide_ratio;
+
+    let oscillator2_frequency = get_frequency_from_midi_note_and_osc_interval(
+        voice.midi_note,
+        oscillators.get_oscillator_interval(2),
+        oscillators.get_oscillator_fine_tune(2),
+        parameters.master_tune,
+    ) * glide_ratio;
+
+    let oscillator3_frequency = get_frequency_from_midi_note_and_osc_interval(
+        voice.midi_note,
+        oscillators.get_oscillator_interval(3),
+        oscillators.get_oscillator_fine_tune(3),
+        parameters.master_tune,
+    ) * glide_ratio;
+
+    let oscillator1_level = oscillators.get_oscillator1_level();
+    let oscillator2_level = oscillators.get_oscillator2_level();
+    let oscillator3_level = oscillators.get_oscillator3_level();
+    let sub_oscillator_level = oscillators.get_sub_oscillator_level();
+
+    let sub_oscillator_sample = oscillators.get_sub_oscillator_next_sample(
+        sub_oscillator_frequency,
+        sub_oscillator_level,
+        oscillator_modulations[0],
+    );
+
+    let oscillator1_sample = oscillators.get_oscillator1_next_sample(
+        oscillator1_frequency,
+        oscillator1_level,
+        oscillator_modulations[1],
+    );
+
+    let oscillator2_sample = oscillators.get_oscillator2_next_sample(
+        oscillator2_frequency,
+        oscillator2_level,
+        oscillator_modulations[2],
+    );
+
+    let oscillator3_sample = oscillators.get_oscillator3_next_sample(
+        oscillator3_frequency,
+        oscillator3_level,
+        oscillator_modulations[3],
+    );
+
+    // Each oscillator goes into filter A, filter B or both, depending on its send and the routing
+    let oscillator_samples = [
+        sub_oscillator_sample,
+        oscillator1_sample,
+        oscillator2_sample,
+        oscillator3_sample,
+    ];
+
+    let mut filter_a_input = (0.0, 0.0);
+    let mut filter_b_input = (0.0, 0.0);
+
+    for (oscillator, (left_sample, right_sample)) in oscillator_samples.into_iter().enumerate() {
+        let filter_send = oscillators.get_oscillator_filter_send(oscillator as i32);
+        let (filter_a_level, filter_b_level) =
+            filter::get_filter_send_levels(filter_send, parameters.filter_routing);
+
+        filter_a_input.0 += left_sample * filter_a_level;
+        filter_a_input.1 += right_sample * filter_a_level;
+        filter_b_input.0 += left_sample * filter_b_level;
+        filter_b_input.1 += right_sample * filter_b_level;
+    }
+
+    let oscillator_level_sum =
+        oscillator1_level + oscillator2_level + oscillator3_level + sub_oscillator_level;
+    let output_level_is_constant = parameters.output_level_constant;
+
+    let balance = |(left_sample, right_sample): (f32, f32)| {
+        (
+            get_balanced_oscillator_sum(
+                oscillator_level_sum,
+                output_level_is_constant,
+                left_sample,
+            ),
+            get_balanced_oscillator_sum(
+                oscillator_level_sum,
+                output_level_is_constant,
+                right_sample,
+            ),
+        )
+    };
+
+    let filter_modulation =
+        get_voice_filter_modulation(voice, parameters, filter_mod_value, is_arpeggiator_voice);
+
+    let filtered_samples = get_filtered_samples(
+        voice,
+        parameters.filter_routing,
+        balance(filter_a_input),
+        balance(filter_b_input),
+        filter_modulation,
+    );
+
+    if parameters.amp_mode == AmpMode::Gate {
+        return match voice.envelope.gate(parameters.output_level) {
+            GateState::On(db_adjustment) | GateState::Off(db_adjustment) => {
+                get_adjusted_samples(filtered_samples, db_adjustment)
+            }
+            GateState::End(db_adjustment) => {
+                if is_arpeggiator_voice {
+                    play_next_arpeggiator_note(voice, parameters);
+                } else if voice.midi_state == MidiState::NoteOff {
+                    voice.midi_state = MidiState::Rest;
+                }
+                get_adjusted_samples(filtered_samples, db_adjustment)
+            }
+        };
+    }
+
+    match voice.envelope.adsr(
+        parameters.output_level,
+        &mut voice.midi_state,
+        is_arpeggiator_voice,
+    ) {
+        ADSRState::Playing(db_adjustment) => get_adjusted_samples(filtered_samples, db_adjustment),
+        ADSRState::Stopped => {
+            if is_arpeggiator_voice {
+                play_next_arpeggiator_note(voice, parameters);
+            } else {
+                voice.midi_state = MidiState::Rest;
+            }
+            (0.0, 0.0)
+        }
+    }
+}
+
+fn get_adjusted_samples(samples: (f32, f32), db_adjustment: f32) -> (f32, f32) {
+    (samples.0 * db_adjustment, samples.1 * db_adjustment)
+}
+
+fn play_next_arpeggiator_note(voice: &mut Voice, parameters: &mut SynthParameters) {
+    let arpeggiator_type = parameters.arpeggiator_type.clone();
+    let next_midi_note = parameters.arpeggiator.next_midi_note(arpeggiator_type);
+
+    if parameters.arpeggiator_glide_is_enabled {
+        voice.glide_to_note(next_midi_note);
+    } else {
+        voice.glide.stop();
+        voice.midi_note = next_midi_note;
+    }
+
+    voice.midi_state = MidiState::NoteOn;
+    voice.oscillators.start_note();
+}
+
+fn get_filter_mod_value(lfo: &mut LFO, parameters: &mut SynthParameters) -> Option<f32> {
+    match parameters.filter_mod.is_enabled {
+        true => Some(lfo.get_next_value(
+            parameters.filter_mod.frequency,
+            parameters.filter_mod.center_value,
+            parameters.filter_mod.width,
+        )),
+        false => None,
+    }
+}
+
+fn get_filtered_samples(
+    voice: &mut Voice,
+    routing: FilterRouting,
+    filter_a_input: (f32, f32),
+    filter_b_input: (f32, f32),
+    modulation: Option<f32>,
+) -> (f32, f32) {
+    let [left_filter_a, left_filter_b] = &mut voice.left_filters;
+    let [right_filter_a, right_filter_b] = &mut voice.right_filters;
+
+    match routing {
+        // Filter A feeds filter B, with anything sent only to B joining in between them
+        FilterRouting::Serial => {
+            let left_sample =
+                left_filter_a.filter_sample(filter_a_input.0, modulation) + filter_b_input.0;
+            let right_sample =
+                right_filter_a.filter_sample(filter_a_input.1, modulation) + filter_b_input.1;
+            (
+                left_filter_b.filter_sample(left_sample, modulation),
+                right_filter_b.filter_sample(right_sample, modulation),
+            )
+        }
+        FilterRouting::Parallel => (
+            left_filter_a.filter_sample(filter_a_input.0, modulation)
+                + left_filter_b.filter_sample(filter_b_input.0, modulation),
+            right_filter_a.filter_sample(filter_a_input.1, modulation)
+                + right_filter_b.filter_sample(filter_b_input.1, modulation),
+        ),
+        // Filter A plays on the left and filter B on the right
+        FilterRouting::Split => (
+            left_filter_a.filter_sample(filter_a_input.0, modulation),
+            right_filter_b.filter_sample(filter_b_input.1, modulation),
+        ),
+    }
+}
+
+// The filter envelope, key tracking and velocity each move the cutoff by octaves, on top of the
+// filter LFO
+fn get_voice_filter_modulation(
+    voice: &mut Voice,
+    parameters: &SynthParameters,
+    filter_mod_value: Option<f32>,
+    is_arpeggiator_voice: bool,
+) -> Option<f32> {
+    // The amp envelope moves a new note on to held, but the gate never does, so in gate mode it
+    // is left to the filter envelope
+    let mut filter_envelope_midi_state = voice.midi_state.clone();
+    let filter_envelope_level = voice
+        .filter_envelope
+        .modulation_level(&mut filter_envelope_midi_state, is_arpeggiator_voice);
+    if parameters.amp_mode == AmpMode::Gate {
+        voice.midi_state = filter_envelope_midi_state;
+    }
+
+    let key_tracking_octaves = if voice.midi_note < FIRST_REST_NOTE {
+        let played_note = voice.midi_note as f32 + voice.glide.get_offset_semitones();
+        (played_note - FILTER_KEY_TRACKING_CENTER_NOTE) / 12.0 * parameters.filter_key_tracking
+    } else {
+        0.0
+    };
+
+    let octaves = filter_envelope_level * parameters.filter_envelope_amount
+        + key_tracking_octaves
+        + voice.velocity * parameters.filter_velocity_amount * FILTER_VELOCITY_RANGE_OCTAVES;
+
+    if octaves == 0.0 {
+        return filter_mod_value;
+    }
+
+    Some(2.0f32.powf(octaves) * filter_mod_value.unwrap_or(1.0))
+}
+
+fn get_balanced_oscillator_sum(
+    oscillator_level_sum: f32,
+    output_level_is_constant: bool,
+    oscillator_sum: f32,
+) -> f32 {
+    match output_level_is_constant {
+        true => oscillator_sum / oscillator_level_sum,
+        false => oscillator_sum / UNBALANCED_OUTPUT_LEVEL_ADJUSTMENT,
+    }
+}
+
+fn get_oscillator_mod_value(lfo: &mut LFO, lfo_parameters: LFOParameters) -> Option<f32> {
+    if lfo_parameters.width > 0.0 {
+        Some(lfo.get_next_value(
+            lfo_parameters.frequency,
+            lfo_parameters.center_value,
+            lfo_parameters.width,
+        ))
+    } else {
+        None
+    }
+}
+
+fn get_compressed_samples(
+    parameters: &mut SynthParameters,
+    dynamics: &Dynamics,
+    left_sample: f32,
+    right_sample: f32,
+) -> (f32, f32) {
+    let left_sample = dynamics.compress(
+        parameters.output_level,
+        parameters.dynamics.compressor_threshold,
+        parameters.dynamics.compressor_ratio,
+        left_sample,
+    );
+
+    let right_sample = dynamics.compress(
+        parameters.output_level,
+        parameters.dynamics.compressor_threshold,
+        parameters.dynamics.compressor_ratio,
+        right_sample,
+    );
+
+    (left_sample, right_sample)
+}
+
+fn get_wavefolded_samples(
+    parameters: &mut SynthParameters,
+    dynamics: &Dynamics,
+    left_sample: f32,
+    right_sample: f32,
+) -> (f32, f32) {
+    let left_sample = dynamics.wave_fold(
+        parameters.output_level,
+        parameters.dynamics.wave_folder_threshold,
+        parameters.dynamics.wave_folder_ratio,
+        left_sample,
+    );
+    let right_sample = dynamics.wave_fold(
+        parameters.output_level,
+        parameters.dynamics.wave_folder_threshold,
+        parameters.dynamics.wave_folder_ratio,
+        right_sample,
+    );
+
+    (left_sample, right_sample)
+}
+
+fn get_limited_samples(
+    parameters: &mut SynthParameters,
+    dynamics: &Dynamics,
+    left_sample: f32,
+    right_sample: f32,
+) -> (f32, f32) {
+    let left_sample = dynamics.limit(
+        parameters.output_level,
+        parameters.dynamics.limiter_threshold,
+        left_sample,
+    );
+    let right_sample = dynamics.limit(
+        parameters.output_level,
+        parameters.dynamics.limiter_threshold,
+        right_sample,
+    );
+
+    (left_sample, right_sample)
+}
+
+fn get_clipped_samples(
+    parameters: &mut SynthParameters,
+    dynamics: &Dynamics,
+    left_sample: f32,
+    right_sample: f32,
+) -> (f32, f32) {
+    let left_sample = dynamics.clip(
+        parameters.output_level,
+        parameters.dynamics.clipper_threshold,
+        left_sample,
+    );
+    let right_sample = dynamics.clip(
+        parameters.output_level,
+        parameters.dynamics.clipper_threshold,
+        right_sample,
+    );
+
+    (left_sample, right_sample)
+}
+
+// Worked out from A4 rather than looked up in the note table, so octave and interval shifts can
+// take an oscillator past either end of the MIDI range without it falling silent
+fn get_frequency_from_midi_note_and_osc_interval(
+    midi_note: u16,
+    interval: i32,
+    fine_tune_cents: f32,
+    master_tune: f32,
+) -> f32 {
+    if midi_note >= FIRST_REST_NOTE {
+        return 0.0;
+    }
+
+    let semitones_from_a4 =
+        midi_note as f32 + interval as f32 - A4_MIDI_NOTE + fine_tune_cents / 100.0;
+
+    master_tune * 2.0f32.powf(semitones_from_a4 / 12.0)
+}
+
+fn get_channel_frame_indexes_from_channel_names(left: &str, right: &str) -> (usize, Option<usize>) {
+    let left_channel: usize = left
+        .parse()
+        .unwrap_or(DEFAULT_AUDIO_OUTPUT_LEFT_FRAME_INDEX);
+    let right_channel = right.parse::<usize>().ok();
+    let left_channel_index = left_channel - CHANNEL_TO_FRAME_INDEX_OFFSET;
+    let right_channel_index = right_channel.map(|channel| channel - CHANNEL_TO_FRAME_INDEX_OFFSET);
+    (left_channel_index, right_channel_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synth::oscillators::noise::DEFAULT_NOISE_SEED;
+    use slint::SharedString;
+
+    const TEST_SAMPLE_RATE: f32 = 48000.0;
+    const TEST_NUMBER_OF_CHANNELS: usize = 2;
+    const TEST_NUMBER_OF_FRAMES: usize = 4800;
+
+    fn get_rendered_samples(engine: &mut Engine) -> Vec<f32> {
+        let mut out = vec![0.0; TEST_NUMBER_OF_FRAMES * TEST_NUMBER_OF_CHANNELS];
+        engine.render(&mut out, TEST_NUMBER_OF_CHANNELS);
+        out
+    }
+
+    #[test]
+    fn renders_silence_without_notes() {
+        let mut engine = Engine::new(DEFAULT_NOISE_SEED, TEST_SAMPLE_RATE);
+
+        assert!(
+            get_rendered_samples(&mut engine)
+                .iter()
+                .all(|sample| *sample == 0.0)
+        );
+    }
+
+    #[test]
+    fn renders_a_note_within_full_scale() {
+        let mut engine = Engine::new(DEFAULT_NOISE_SEED, TEST_SAMPLE_RATE);
+        engine.handle_event(&EventType::MidiNoteOn(60, 127));
+        let samples = get_rendered_samples(&mut engine);
+
+        assert!(
+            samples
+                .iter()
+                .all(|sample| sample.is_finite() && sample.abs() <= 1.0)
+        );
+        assert!(samples.iter().any(|sample| sample.abs() > 0.01));
+    }
+
+    #[test]
+    fn the_same_seed_renders_the_same_noise() {
+        let get_noise_samples = |seed| {
+            let mut engine = Engine::new(seed, TEST_SAMPLE_RATE);
+            engine.handle_event(&EventType::UpdateOscillatorShape(
+                SharedString::from("Noise"),
+                1,
+            ));
+            engine.handle_event(&EventType::MidiNoteOn(60, 127));
+            get_rendered_samples(&mut engine)
+        };
+
+        assert_eq!(get_noise_samples(1), get_noise_samples(1));
+        assert_ne!(get_noise_samples(1), get_noise_samples(2));
+    }
+}
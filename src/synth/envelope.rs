@@ -43,6 +43,7 @@ struct Gate {
     state: GateState,
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, PartialEq)]
 struct ADSR {
     current_level: f32,
@@ -118,6 +119,11 @@ impl Envelope {
         self.envelope.sustain_level = level;
     }
 
+    // For an envelope driving a modulation, where the sustain is a proportion of the full sweep
+    pub fn set_sustain_proportion(&mut self, proportion: f32) {
+        self.envelope.sustain_level = -MINIMUM_ENV_LEVEL * (1.0 - proportion.clamp(0.0, 1.0));
+    }
+
     pub fn set_gate_duty_cycle(&mut self, duty_cycle: f32) {
         if duty_cycle <= 0.0 {
             self.gate.duty_cycle = 0.0;
@@ -148,6 +154,16 @@ impl Envelope {
         self.gate.note_length_milliseconds = note_length_milliseconds as f32;
     }
 
+    pub fn restart_gate(&mut self) {
+        self.gate.on_sample_count = DEFAULT_STATE_COUNT_VALUE;
+        self.gate.off_sample_count = DEFAULT_STATE_COUNT_VALUE;
+        self.gate.state = GateState::On(GATE_OFF_SAMPLE_VALUE);
+    }
+
+    pub fn get_current_level(&self) -> f32 {
+        self.envelope.current_level
+    }
+
     pub fn gate(&mut self, output_level: f32) -> GateState {
         match self.gate.state {
             GateState::On(_) => {
@@ -271,6 +287,21 @@ impl Envelope {
         ADSRState::Playing(get_f32_sample_from_dbfs(self.envelope.current_level))
     }
 
+    // Runs the ADSR as a modulation source, from 0 at rest to 1 at the top of the attack. The
+    // stages move in steps of dB, so they come out as straight lines from 0 to 1
+    pub fn modulation_level(
+        &mut self,
+        midi_state: &mut MidiState,
+        arpeggiator_is_active: bool,
+    ) -> f32 {
+        match self.adsr(0.0, midi_state, arpeggiator_is_active) {
+            ADSRState::Playing(_) => {
+                (1.0 - self.envelope.current_level / MINIMUM_ENV_LEVEL).clamp(0.0, 1.0)
+            }
+            ADSRState::Stopped => 0.0,
+        }
+    }
+
     fn get_increment_from_milliseconds(
         &self,
         milliseconds: u32,
@@ -1,8 +1,26 @@
-use std::f32::consts::PI;
+use std::f32::consts::{PI, SQRT_2};
 
+pub const NUMBER_OF_FILTERS: usize = 2;
 const RESONANCE_Q: f32 = 0.0;
 const DEFAULT_CUTOFF_FREQUENCY: f32 = 18500.0;
 const FILTER_MAX_CUTOFF_BEFORE_BYPASSING: f32 = 18499.0;
+const HIGHEST_CUTOFF_PROPORTION_OF_SAMPLE_RATE: f32 = 0.49;
+
+// The resonance slider's full travel, mapped from a Butterworth response to a narrow ringing peak
+const MAXIMUM_RESONANCE: f32 = 0.6;
+const BUTTERWORTH_DAMPING: f32 = SQRT_2;
+const MINIMUM_DAMPING: f32 = 0.05;
+
+// The ladder rings on its own from a feedback of 4. Going a little past it makes sure the full
+// resonance self-oscillates, with the saturation holding the oscillation at a steady level
+const MAXIMUM_LADDER_FEEDBACK: f32 = 4.5;
+const NUMBER_OF_LADDER_STAGES: usize = 4;
+
+// How much of the input is fed back in with the resonance, to make up the bass a ladder loses as
+// the resonance goes up
+const LADDER_BASS_COMPENSATION: f32 = 0.5;
+const DEFAULT_DRIVE: f32 = 1.0;
+const MAXIMUM_DRIVE: f32 = 8.0;
 
 #[derive(Default)]
 pub enum Poles {
@@ -13,20 +31,154 @@ pub enum Poles {
     Four,
 }
 
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum FilterRouting {
+    #[default]
+    Serial,
+    Parallel,
+    Split,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum FilterSend {
+    #[default]
+    A,
+    B,
+    Both,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum FilterModel {
+    #[default]
+    StateVariable,
+    Ladder,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum FilterType {
+    #[default]
+    LowPass,
+    HighPass,
+    BandPass,
+    Notch,
+    Peak,
+    AllPass,
+}
+
+// Trapezoidal integrator state variable filter, after Andrew Simper's Cytomic design. It stays
+// stable and in tune right up to Nyquist, however fast the cutoff moves
+#[derive(Default)]
+struct StateVariableStage {
+    integrator1_state: f32,
+    integrator2_state: f32,
+}
+
+impl StateVariableStage {
+    fn filter_sample(
+        &mut self,
+        sample: f32,
+        gain: f32,
+        damping: f32,
+        filter_type: FilterType,
+    ) -> f32 {
+        let a1 = 1.0 / (1.0 + gain * (gain + damping));
+        let a2 = gain * a1;
+        let a3 = gain * a2;
+
+        let v3 = sample - self.integrator2_state;
+        let band_pass = a1 * self.integrator1_state + a2 * v3;
+        let low_pass = self.integrator2_state + a2 * self.integrator1_state + a3 * v3;
+        self.integrator1_state = 2.0 * band_pass - self.integrator1_state;
+        self.integrator2_state = 2.0 * low_pass - self.integrator2_state;
+
+        let high_pass = sample - damping * band_pass - low_pass;
+
+        match filter_type {
+            FilterType::LowPass => low_pass,
+            FilterType::HighPass => high_pass,
+            // Scaled by the damping so the peak stays at unity as the resonance narrows it
+            FilterType::BandPass => damping * band_pass,
+            FilterType::Notch => low_pass + high_pass,
+            FilterType::Peak => low_pass - high_pass,
+            FilterType::AllPass => sample - 2.0 * damping * band_pass,
+        }
+    }
+}
+
+// Trapezoidal one pole, for the odd pole counts of the low and high pass
+#[derive(Default)]
+struct OnePoleStage {
+    state: f32,
+}
+
+impl OnePoleStage {
+    fn filter_sample(&mut self, sample: f32, gain: f32, filter_type: FilterType) -> f32 {
+        let v = (sample - self.state) * gain / (1.0 + gain);
+        let low_pass = v + self.state;
+        self.state = low_pass + v;
+
+        match filter_type {
+            FilterType::HighPass => sample - low_pass,
+            _ => low_pass,
+        }
+    }
+}
+
+// Four saturating one poles in a row, with the last one fed back into the first like a Moog
+// ladder. The feedback is solved for the current sample rather than taken from the last one, so
+// the resonance stays on the cutoff all the way up the range
+#[derive(Default)]
+struct LadderStages {
+    states: [f32; NUMBER_OF_LADDER_STAGES],
+}
+
+impl LadderStages {
+    fn filter_sample(
+        &mut self,
+        sample: f32,
+        gain: f32,
+        feedback: f32,
+        number_of_poles: usize,
+    ) -> f32 {
+        let stage_gain = gain / (1.0 + gain);
+
+        // What the last stage would give with no input, from where each stage's state leaves it
+        let state_contribution = self
+            .states
+            .iter()
+            .fold(0.0, |sum, state| sum * stage_gain + state / (1.0 + gain));
+
+        let input = (sample * (1.0 + feedback * LADDER_BASS_COMPENSATION)
+            - feedback * state_contribution)
+            / (1.0 + feedback * stage_gain.powi(NUMBER_OF_LADDER_STAGES as i32));
+
+        let mut stage_input = input;
+        let mut outputs = [0.0; NUMBER_OF_LADDER_STAGES];
+
+        for (state, output) in self.states.iter_mut().zip(outputs.iter_mut()) {
+            let v = (stage_input.tanh() - *state) * stage_gain;
+            *output = v + *state;
+            *state = *output + v;
+            stage_input = *output;
+        }
+
+        outputs[number_of_poles - 1]
+    }
+}
+
 #[derive(Default)]
 pub struct Filter {
     sample_rate: f32,
     cutoff_frequency: f32,
     resonance_q: f32,
     number_of_poles: Poles,
-    pole1_buffer_0: f32,
-    pole1_buffer_1: f32,
-    pole2_buffer_0: f32,
-    pole2_buffer_1: f32,
-    pole3_buffer_0: f32,
-    pole3_buffer_1: f32,
-    pole4_buffer_0: f32,
-    pole4_buffer_1: f32,
+    filter_type: FilterType,
+    model: FilterModel,
+    drive: f32,
+    first_stage: StateVariableStage,
+    second_stage: StateVariableStage,
+    one_pole_stage: OnePoleStage,
+    ladder_stages: LadderStages,
 }
 
 impl Filter {
@@ -35,98 +187,177 @@ impl Filter {
             sample_rate,
             cutoff_frequency: DEFAULT_CUTOFF_FREQUENCY,
             resonance_q: RESONANCE_Q,
+            drive: DEFAULT_DRIVE,
             ..Self::default()
         }
     }
 
+    // The modulation scales the cutoff, so it can sweep it above the knob as well as below
     pub fn filter_sample(&mut self, sample: f32, modulation: Option<f32>) -> f32 {
-        if self.cutoff_frequency > FILTER_MAX_CUTOFF_BEFORE_BYPASSING {
+        let modulated_cutoff_frequency = self.cutoff_frequency * modulation.unwrap_or(1.0);
+
+        // A low pass opened all the way passes everything, so it is skipped. The other types still
+        // have work to do at the top of the range. The stages are left as they are while skipped,
+        // never reset
+        if self.get_filter_type() == FilterType::LowPass
+            && modulated_cutoff_frequency > FILTER_MAX_CUTOFF_BEFORE_BYPASSING
+        {
             return sample;
         }
 
-        let mut cutoff_frequency = self
-            .cutoff_frequency
-            .min(self.cutoff_frequency * modulation.unwrap_or(1.0));
+        let cutoff_frequency = modulated_cutoff_frequency.clamp(
+            0.0,
+            self.sample_rate * HIGHEST_CUTOFF_PROPORTION_OF_SAMPLE_RATE,
+        );
+
+        let gain = get_integrator_gain(cutoff_frequency, self.sample_rate);
 
-        if cutoff_frequency.is_sign_negative() {
-            cutoff_frequency = 0.0;
+        if self.model == FilterModel::Ladder {
+            let feedback = get_ladder_feedback(self.resonance_q);
+            let number_of_poles = get_integer_from_number_of_poles(&self.number_of_poles);
+            return self.ladder_stages.filter_sample(
+                sample * self.drive,
+                gain,
+                feedback,
+                number_of_poles,
+            );
         }
 
-        let normalized_frequency = get_normalized_frequency(cutoff_frequency, self.sample_rate);
-        let feedback = get_feedback_amount(self.resonance_q, normalized_frequency);
-        self.filter_sample_pole1(sample, normalized_frequency, feedback);
+        let damping = get_damping(self.resonance_q);
 
-        match self.number_of_poles {
-            Poles::One => self.pole1_buffer_1,
-            Poles::Two => {
-                self.filter_sample_pole2(normalized_frequency, feedback);
-                self.pole2_buffer_1
+        // Only the first stage resonates. The stages after it just steepen the slope
+        match (self.filter_type, &self.number_of_poles) {
+            (FilterType::LowPass | FilterType::HighPass, Poles::One) => self
+                .one_pole_stage
+                .filter_sample(sample, gain, self.filter_type),
+            (FilterType::LowPass | FilterType::HighPass, Poles::Three) => {
+                let sample =
+                    self.first_stage
+                        .filter_sample(sample, gain, damping, self.filter_type);
+                self.one_pole_stage
+                    .filter_sample(sample, gain, self.filter_type)
             }
-            Poles::Three => {
-                self.filter_sample_pole2(normalized_frequency, feedback);
-                self.filter_sample_pole3(normalized_frequency, feedback);
-                self.pole3_buffer_1
+            // Band pass, notch, peak and all pass have no odd slopes, so one and two poles
+            // share a single stage, and three and four poles share two
+            (_, Poles::One | Poles::Two) => {
+                self.first_stage
+                    .filter_sample(sample, gain, damping, self.filter_type)
             }
-            Poles::Four => {
-                self.filter_sample_pole2(normalized_frequency, feedback);
-                self.filter_sample_pole3(normalized_frequency, feedback);
-                self.filter_sample_pole4(normalized_frequency, feedback);
-                self.pole4_buffer_1
+            (_, Poles::Three | Poles::Four) => {
+                let sample =
+                    self.first_stage
+                        .filter_sample(sample, gain, damping, self.filter_type);
+                self.second_stage
+                    .filter_sample(sample, gain, BUTTERWORTH_DAMPING, self.filter_type)
             }
         }
     }
 
-    fn filter_sample_pole1(&mut self, sample: f32, normalized_frequency: f32, feedback: f32) {
-        let high_pass = sample - self.pole1_buffer_0;
-        let band_pass = self.pole1_buffer_0 - self.pole1_buffer_1;
-        self.pole1_buffer_0 += normalized_frequency * (high_pass + feedback * band_pass);
-        self.pole1_buffer_1 = self.pole1_buffer_1
-            + normalized_frequency * (self.pole1_buffer_0 - self.pole1_buffer_1);
+    pub fn set_cutoff_frequency(&mut self, cutoff_frequency: f32) {
+        self.cutoff_frequency = cutoff_frequency;
     }
 
-    fn filter_sample_pole2(&mut self, normalized_frequency: f32, feedback: f32) {
-        let high_pass = self.pole1_buffer_1 - self.pole2_buffer_0;
-        let band_pass = self.pole2_buffer_0 - self.pole2_buffer_1;
-        self.pole2_buffer_0 += normalized_frequency * (high_pass + feedback * band_pass);
-        self.pole2_buffer_1 = self.pole2_buffer_1
-            + normalized_frequency * (self.pole2_buffer_0 - self.pole2_buffer_1);
+    pub fn set_resonance(&mut self, resonance: f32) {
+        self.resonance_q = resonance;
     }
 
-    fn filter_sample_pole3(&mut self, normalized_frequency: f32, feedback: f32) {
-        let high_pass = self.pole2_buffer_1 - self.pole3_buffer_0;
-        let band_pass = self.pole3_buffer_0 - self.pole3_buffer_1;
-        self.pole3_buffer_0 += normalized_frequency * (high_pass + feedback * band_pass);
-        self.pole3_buffer_1 = self.pole3_buffer_1
-            + normalized_frequency * (self.pole3_buffer_0 - self.pole3_buffer_1);
+    pub fn set_number_of_poles(&mut self, number_of_poles: i32) {
+        self.number_of_poles = get_number_of_poles_from_integer(number_of_poles);
     }
 
-    fn filter_sample_pole4(&mut self, normalized_frequency: f32, feedback: f32) {
-        let high_pass = self.pole3_buffer_1 - self.pole4_buffer_0;
-        let band_pass = self.pole4_buffer_0 - self.pole4_buffer_1;
-        self.pole4_buffer_0 += normalized_frequency * (high_pass + feedback * band_pass);
-        self.pole4_buffer_1 = self.pole4_buffer_1
-            + normalized_frequency * (self.pole4_buffer_0 - self.pole4_buffer_1);
+    pub fn set_filter_type(&mut self, filter_type: FilterType) {
+        self.filter_type = filter_type;
     }
 
-    pub fn set_cutoff_frequency(&mut self, cutoff_frequency: f32) {
-        self.cutoff_frequency = cutoff_frequency;
+    pub fn set_filter_model(&mut self, model: FilterModel) {
+        self.model = model;
     }
 
-    pub fn set_resonance(&mut self, resonance: f32) {
-        self.resonance_q = resonance;
+    // How hard the ladder is pushed into its saturation
+    pub fn set_drive(&mut self, drive: f32) {
+        self.drive = drive.clamp(DEFAULT_DRIVE, MAXIMUM_DRIVE);
     }
 
-    pub fn set_number_of_poles(&mut self, number_of_poles: i32) {
-        self.number_of_poles = get_number_of_poles_from_integer(number_of_poles);
+    // The ladder only comes as a low pass
+    fn get_filter_type(&self) -> FilterType {
+        match self.model {
+            FilterModel::StateVariable => self.filter_type,
+            FilterModel::Ladder => FilterType::LowPass,
+        }
     }
 }
 
-fn get_normalized_frequency(cutoff_frequency: f32, sample_rate: f32) -> f32 {
-    PI * (cutoff_frequency / sample_rate)
+pub fn get_filter_type_from_type_name(type_name: &str) -> FilterType {
+    match type_name {
+        "Low Pass" => FilterType::LowPass,
+        "High Pass" => FilterType::HighPass,
+        "Band Pass" => FilterType::BandPass,
+        "Notch" => FilterType::Notch,
+        "Peak" => FilterType::Peak,
+        "All Pass" => FilterType::AllPass,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_filter_routing_from_routing_name(routing_name: &str) -> FilterRouting {
+    match routing_name {
+        "Serial" => FilterRouting::Serial,
+        "Parallel" => FilterRouting::Parallel,
+        "Split" => FilterRouting::Split,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_filter_send_from_send_name(send_name: &str) -> FilterSend {
+    match send_name {
+        "A" => FilterSend::A,
+        "B" => FilterSend::B,
+        "A + B" => FilterSend::Both,
+        _ => Default::default(),
+    }
+}
+
+// How much of an oscillator goes into filters A and B. In series everything going into A carries
+// on through B, so sending to both is the same as sending to A. In parallel a shared oscillator
+// is halved between them, so it plays at the same level as through one filter
+pub fn get_filter_send_levels(filter_send: FilterSend, routing: FilterRouting) -> (f32, f32) {
+    match (filter_send, routing) {
+        (FilterSend::A, _) | (FilterSend::Both, FilterRouting::Serial) => (1.0, 0.0),
+        (FilterSend::B, _) => (0.0, 1.0),
+        (FilterSend::Both, FilterRouting::Parallel) => (0.5, 0.5),
+        (FilterSend::Both, FilterRouting::Split) => (1.0, 1.0),
+    }
 }
 
-fn get_feedback_amount(resonance_q: f32, normalized_frequency: f32) -> f32 {
-    resonance_q + resonance_q / (1.0 - normalized_frequency)
+pub fn get_filter_model_from_model_name(model_name: &str) -> FilterModel {
+    match model_name {
+        "State Variable" => FilterModel::StateVariable,
+        "Ladder" => FilterModel::Ladder,
+        _ => Default::default(),
+    }
+}
+
+// Prewarped, so the cutoff lands where it should instead of sagging towards Nyquist
+fn get_integrator_gain(cutoff_frequency: f32, sample_rate: f32) -> f32 {
+    (PI * cutoff_frequency / sample_rate).tan()
+}
+
+fn get_damping(resonance_q: f32) -> f32 {
+    let resonance = (resonance_q / MAXIMUM_RESONANCE).clamp(0.0, 1.0);
+    BUTTERWORTH_DAMPING - (BUTTERWORTH_DAMPING - MINIMUM_DAMPING) * resonance
+}
+
+fn get_ladder_feedback(resonance_q: f32) -> f32 {
+    (resonance_q / MAXIMUM_RESONANCE).clamp(0.0, 1.0) * MAXIMUM_LADDER_FEEDBACK
+}
+
+fn get_integer_from_number_of_poles(number_of_poles: &Poles) -> usize {
+    match number_of_poles {
+        Poles::One => 1,
+        Poles::Two => 2,
+        Poles::Three => 3,
+        Poles::Four => 4,
+    }
 }
 
 fn get_number_of_poles_from_integer(number_of_poles_word: i32) -> Poles {
@@ -0,0 +1,127 @@
+const DEFAULT_GLIDE_TIME_MILLISECONDS: u32 = 100;
+const SEMITONES_PER_OCTAVE: f32 = 12.0;
+const GLIDE_END_THRESHOLD_SEMITONES: f32 = 0.001;
+const EXPONENTIAL_GLIDE_TIME_CONSTANTS: f32 = 6.9;
+const MINIMUM_GLIDE_SAMPLES: f32 = 1.0;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum GlideCurve {
+    #[default]
+    Linear,
+    Exponential,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum GlideMode {
+    #[default]
+    ConstantTime,
+    ConstantRate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glide {
+    sample_rate: f32,
+    glide_time_milliseconds: u32,
+    curve: GlideCurve,
+    mode: GlideMode,
+    offset_semitones: f32,
+    linear_increment: f32,
+    exponential_coefficient: f32,
+}
+
+impl Glide {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            glide_time_milliseconds: DEFAULT_GLIDE_TIME_MILLISECONDS,
+            curve: GlideCurve::default(),
+            mode: GlideMode::default(),
+            offset_semitones: 0.0,
+            linear_increment: 0.0,
+            exponential_coefficient: 0.0,
+        }
+    }
+
+    pub fn set_glide_time_milliseconds(&mut self, milliseconds: u32) {
+        self.glide_time_milliseconds = milliseconds;
+    }
+
+    pub fn set_curve(&mut self, curve: GlideCurve) {
+        self.curve = curve;
+    }
+
+    pub fn set_mode(&mut self, mode: GlideMode) {
+        self.mode = mode;
+    }
+
+    pub fn get_offset_semitones(&self) -> f32 {
+        self.offset_semitones
+    }
+
+    pub fn glide_between_notes(&mut self, from_note: f32, to_note: f32) {
+        self.offset_semitones = from_note - to_note;
+
+        if self.glide_time_milliseconds == 0 {
+            self.offset_semitones = 0.0;
+            return;
+        }
+
+        // In constant rate mode the glide time is how long an octave takes, so longer jumps take longer
+        let glide_time_milliseconds = match self.mode {
+            GlideMode::ConstantTime => self.glide_time_milliseconds as f32,
+            GlideMode::ConstantRate => {
+                self.glide_time_milliseconds as f32 * self.offset_semitones.abs()
+                    / SEMITONES_PER_OCTAVE
+            }
+        };
+
+        let number_of_samples =
+            (glide_time_milliseconds * self.sample_rate / 1000.0).max(MINIMUM_GLIDE_SAMPLES);
+
+        self.linear_increment = self.offset_semitones.abs() / number_of_samples;
+        self.exponential_coefficient =
+            (-EXPONENTIAL_GLIDE_TIME_CONSTANTS / number_of_samples).exp();
+    }
+
+    pub fn stop(&mut self) {
+        self.offset_semitones = 0.0;
+    }
+
+    pub fn next_frequency_ratio(&mut self) -> f32 {
+        if self.offset_semitones == 0.0 {
+            return 1.0;
+        }
+
+        match self.curve {
+            GlideCurve::Linear => {
+                let step = self.linear_increment.min(self.offset_semitones.abs());
+                self.offset_semitones -= step.copysign(self.offset_semitones);
+            }
+            GlideCurve::Exponential => {
+                self.offset_semitones *= self.exponential_coefficient;
+            }
+        }
+
+        if self.offset_semitones.abs() < GLIDE_END_THRESHOLD_SEMITONES {
+            self.offset_semitones = 0.0;
+        }
+
+        2.0_f32.powf(self.offset_semitones / SEMITONES_PER_OCTAVE)
+    }
+}
+
+pub fn get_glide_curve_from_curve_name(curve_name: &str) -> GlideCurve {
+    match curve_name {
+        "Linear" => GlideCurve::Linear,
+        "Exponential" => GlideCurve::Exponential,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_glide_mode_from_mode_name(mode_name: &str) -> GlideMode {
+    match mode_name {
+        "Constant Time" => GlideMode::ConstantTime,
+        "Constant Rate" => GlideMode::ConstantRate,
+        _ => Default::default(),
+    }
+}
@@ -1,5 +1,7 @@
 use crate::synth::oscillators::GenerateSamples;
+use std::mem;
 
+#[allow(clippy::upper_case_acronyms)]
 pub struct LFO {
     oscillator: Box<dyn GenerateSamples + Send + Sync>,
 }
@@ -15,6 +17,13 @@ impl LFO {
         center_value + (wave_position * (range / 2.0))
     }
 
+    pub fn replace_oscillator(
+        &mut self,
+        oscillator: Box<dyn GenerateSamples + Send + Sync>,
+    ) -> Box<dyn GenerateSamples + Send + Sync> {
+        mem::replace(&mut self.oscillator, oscillator)
+    }
+
     pub fn reset(&mut self) {
         self.oscillator.reset();
     }
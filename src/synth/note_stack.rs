@@ -0,0 +1,120 @@
+const NUMBER_OF_MIDI_NOTES: usize = 128;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoteStack {
+    notes: Vec<u16>,
+}
+
+impl NoteStack {
+    pub fn new() -> Self {
+        Self {
+            notes: Vec::with_capacity(NUMBER_OF_MIDI_NOTES),
+        }
+    }
+
+    pub fn push(&mut self, midi_note: u16) {
+        self.remove(midi_note);
+        self.notes.push(midi_note);
+    }
+
+    pub fn remove(&mut self, midi_note: u16) {
+        self.notes.retain(|held_note| *held_note != midi_note);
+    }
+
+    pub fn clear(&mut self) {
+        self.notes.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<u16> {
+        self.notes.last().copied()
+    }
+
+    pub fn lowest(&self) -> Option<u16> {
+        self.notes.iter().min().copied()
+    }
+
+    pub fn highest(&self) -> Option<u16> {
+        self.notes.iter().max().copied()
+    }
+
+    // Most recently pressed first, which is the order a released key falls back through
+    pub fn iter_latest_first(&self) -> impl Iterator<Item = u16> + '_ {
+        self.notes.iter().rev().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_note_stack(notes: &[u16]) -> NoteStack {
+        let mut note_stack = NoteStack::new();
+        notes
+            .iter()
+            .for_each(|midi_note| note_stack.push(*midi_note));
+        note_stack
+    }
+
+    #[test]
+    fn push_and_remove() {
+        let mut note_stack = get_note_stack(&[60, 64, 67]);
+        assert_eq!(
+            note_stack.iter_latest_first().collect::<Vec<_>>(),
+            [67, 64, 60]
+        );
+
+        note_stack.remove(64);
+        assert_eq!(note_stack.iter_latest_first().collect::<Vec<_>>(), [67, 60]);
+
+        note_stack.remove(72);
+        assert_eq!(note_stack.iter_latest_first().collect::<Vec<_>>(), [67, 60]);
+
+        note_stack.remove(67);
+        note_stack.remove(60);
+        assert!(note_stack.is_empty());
+        assert_eq!(note_stack.latest(), None);
+    }
+
+    #[test]
+    fn pushing_a_held_note_moves_it_to_the_top() {
+        let mut note_stack = get_note_stack(&[60, 64, 67]);
+        note_stack.push(60);
+
+        assert_eq!(
+            note_stack.iter_latest_first().collect::<Vec<_>>(),
+            [60, 67, 64]
+        );
+        assert_eq!(note_stack.latest(), Some(60));
+    }
+
+    #[test]
+    fn latest_lowest_and_highest() {
+        let note_stack = get_note_stack(&[64, 72, 48, 60]);
+
+        assert_eq!(note_stack.latest(), Some(60));
+        assert_eq!(note_stack.lowest(), Some(48));
+        assert_eq!(note_stack.highest(), Some(72));
+    }
+
+    #[test]
+    fn falls_back_after_removal() {
+        let mut note_stack = get_note_stack(&[64, 72, 48, 60]);
+
+        note_stack.remove(60);
+        assert_eq!(note_stack.latest(), Some(48));
+
+        note_stack.remove(48);
+        assert_eq!(note_stack.latest(), Some(72));
+        assert_eq!(note_stack.lowest(), Some(64));
+
+        note_stack.remove(72);
+        assert_eq!(note_stack.highest(), Some(64));
+
+        note_stack.clear();
+        assert!(note_stack.is_empty());
+    }
+}
@@ -1,32 +1,70 @@
+pub mod additive;
 pub mod am;
 pub mod fm;
+pub mod four_operator_fm;
+pub mod morph;
 pub mod noise;
+pub mod pluck;
+pub mod poly_blep;
 pub mod pulse;
 pub mod ramp;
+pub mod sampler;
 pub mod saw;
 pub mod sine;
 pub mod square;
 pub mod sub;
 pub mod super_saw;
 pub mod triangle;
+pub mod unison;
+pub mod wavetable;
 
+use crate::synth::filter::FilterSend;
+use additive::{Additive, AdditivePreset};
 use am::AM;
 use fm::FM;
+use four_operator_fm::{
+    FourOperatorFM, NUMBER_OF_OPERATOR_PARAMETERS, NUMBER_OF_OPERATORS, OperatorParameter,
+};
+use morph::Morph;
 use noise::Noise;
+use pluck::Pluck;
 use pulse::Pulse;
 use ramp::Ramp;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use sampler::{LoopMode, SampleData, SampleInterpolation, Sampler};
 use saw::Saw;
 use sine::Sine;
-use slint::SharedString;
 use square::Square;
+use std::f32::consts::TAU;
+use std::mem;
+use std::sync::Arc;
 use sub::Sub;
 use super_saw::SuperSaw;
 use triangle::Triangle;
+use unison::Unison;
+use wavetable::{Wavetable, WavetableData};
 
 const DEFAULT_WAVE_LEVEL: f32 = 1.0;
 const DEFAULT_SUB_LEVEL: f32 = 0.0;
 const DEFAULT_WAVE_SHAPER_AMOUNT: f32 = 0.0;
 const DEFAULT_WAVE_INTERVAL: i32 = 0;
+const DEFAULT_WAVE_OCTAVE: i32 = 0;
+const DEFAULT_WAVE_FINE_TUNE: f32 = 0.0;
+const DEFAULT_SUB_OCTAVE: i32 = -1;
+const MAXIMUM_OCTAVE_OFFSET: i32 = 3;
+const MAXIMUM_FINE_TUNE_CENTS: f32 = 100.0;
+const SEMITONES_PER_OCTAVE: i32 = 12;
+pub const DEFAULT_UNISON_VOICES: i32 = 1;
+const DEFAULT_UNISON_DETUNE: f32 = 0.0;
+const DEFAULT_UNISON_WIDTH: f32 = 0.0;
+const DEFAULT_FM_AMOUNT: f32 = 0.0;
+const MAXIMUM_FM_AMOUNT: f32 = 8.0;
+const SHAPE_CHANGE_FADE_SECONDS: f32 = 0.005;
+const NUMBER_OF_SHAPE_SETTINGS_BEFORE_FM_OPERATORS: usize = 8;
+const NUMBER_OF_SHAPE_SETTINGS: usize = NUMBER_OF_SHAPE_SETTINGS_BEFORE_FM_OPERATORS
+    + NUMBER_OF_OPERATORS * NUMBER_OF_OPERATOR_PARAMETERS;
+pub const NUMBER_OF_OSCILLATORS: usize = 4;
 
 pub trait GenerateSamples {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32;
@@ -34,6 +72,114 @@ pub trait GenerateSamples {
     fn set_shape_specific_parameters(&mut self, parameters: (f32, f32));
 
     fn reset(&mut self);
+
+    // Oscillators without a stereo image play the same sample on both sides
+    fn next_stereo_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> (f32, f32) {
+        let sample = self.next_sample(tone_frequency, modulation);
+        (sample, sample)
+    }
+
+    // Whether the last sample finished a cycle, for hard syncing other oscillators
+    fn has_phase_wrapped(&self) -> bool {
+        false
+    }
+
+    fn hard_sync(&mut self) {
+        self.reset();
+    }
+
+    fn set_quality(&mut self, _quality: OscillatorQuality) {}
+
+    // Note events, for oscillators with envelopes or that need exciting
+    fn start_note(&mut self) {}
+
+    fn release_note(&mut self) {}
+
+    // Jumps to a point in the cycle, from 0 to 1. Oscillators without a single phase ignore it
+    fn set_phase(&mut self, _phase: f32) {}
+
+    // Settings only some shapes have. Each shape picks out the ones it uses and ignores the rest,
+    // so the sub and unison wrappers have just this one method to pass them on through
+    fn set_shape_parameter(&mut self, _parameter: ShapeParameter) {}
+
+    // Leaves the previous table or sample in `shape_data`. Oscillators without one leave it
+    // untouched
+    fn swap_shape_data(&mut self, _shape_data: &mut ShapeData) {}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ShapeParameter {
+    SuperSawVoices(i32),
+    SuperSawStereoSpread(f32),
+    FMAlgorithm(i32),
+    FMOperator(i32, OperatorParameter, f32),
+    AdditivePreset(AdditivePreset),
+    AdditiveStretch(f32),
+    SamplerRootFrequency(f32),
+    SamplerLoopMode(LoopMode),
+    SamplerInterpolation(SampleInterpolation),
+    SubOctave(i32),
+    UnisonVoices(i32),
+    UnisonDetune(f32),
+    UnisonWidth(f32),
+    UnisonPhaseRandomized(bool),
+}
+
+// Loaded off the audio thread and shared between every voice playing it
+#[derive(Clone)]
+pub enum ShapeData {
+    Wavetable(Arc<WavetableData>),
+    Sample(Arc<SampleData>),
+}
+
+// The shape specific settings a wave has been given, so a wave rebuilt for a new number of unison
+// voices can be given them again. Each setting has a fixed slot, so keeping them never allocates
+#[derive(Copy, Clone)]
+struct ShapeSettings {
+    specific_parameters: Option<(f32, f32)>,
+    parameters: [Option<ShapeParameter>; NUMBER_OF_SHAPE_SETTINGS],
+}
+
+impl ShapeSettings {
+    fn new() -> Self {
+        Self {
+            specific_parameters: None,
+            parameters: [None; NUMBER_OF_SHAPE_SETTINGS],
+        }
+    }
+
+    fn store_parameter(&mut self, parameter: ShapeParameter) {
+        if let Some(slot) = get_shape_setting_slot(&parameter) {
+            self.parameters[slot] = Some(parameter);
+        }
+    }
+
+    fn apply(&self, wave: &mut Box<dyn GenerateSamples + Send + Sync>) {
+        if let Some(parameters) = self.specific_parameters {
+            wave.set_shape_specific_parameters(parameters);
+        }
+
+        self.parameters
+            .iter()
+            .flatten()
+            .for_each(|parameter| wave.set_shape_parameter(*parameter));
+    }
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum OscillatorQuality {
+    Naive,
+    #[default]
+    BandLimited,
+}
+
+// Linear FM stops at 0 Hz, through zero FM carries on and runs the wave backwards
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum FMMode {
+    #[default]
+    Linear,
+    ThroughZero,
+    Phase,
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -49,6 +195,12 @@ pub enum WaveShape {
     Triangle,
     FM,
     AM,
+    Wavetable,
+    FourOperatorFM,
+    Pluck,
+    Additive,
+    Sampler,
+    Morph,
 }
 
 struct Parameters {
@@ -57,68 +209,225 @@ struct Parameters {
     level: f32,
     drive_amount: f32,
     interval: i32,
+    octave: i32,
+    fine_tune: f32,
+    unison_voices: i32,
+    unison_detune: f32,
+    unison_width: f32,
+    unison_phase_is_randomized: bool,
+    shape_settings: ShapeSettings,
+    sync_source: Option<usize>,
+    ring_modulation_source: Option<usize>,
+    fm_source: Option<usize>,
+    fm_mode: FMMode,
+    fm_amount: f32,
+    filter_send: FilterSend,
+    previous_phase_offset: f32,
+    last_samples: (f32, f32),
+    shape_change_samples: (f32, f32),
+    shape_change_fade: f32,
 }
 
 pub struct Oscillators {
     sample_rate: f32,
-    is_unison: bool,
-    unison_frequency_offset: f32,
-    oscillators: [Parameters; 4],
+    seed_generator: SmallRng,
+    quality: OscillatorQuality,
+    sub_octave: i32,
+    oscillators: [Parameters; NUMBER_OF_OSCILLATORS],
 }
 
 impl Oscillators {
-    pub fn new(sample_rate: f32) -> Self {
+    pub fn new(seed: u64, sample_rate: f32) -> Self {
+        let mut seed_generator = SmallRng::seed_from_u64(seed);
+
         let sub_oscillator = Parameters {
-            wave: Box::new(Sine::new(sample_rate)),
+            wave: get_oscillator_wave_for_slot(
+                &WaveShape::Sine,
+                0,
+                DEFAULT_UNISON_VOICES,
+                &mut seed_generator,
+                sample_rate,
+            ),
             shape: WaveShape::Sine,
             level: DEFAULT_SUB_LEVEL,
             drive_amount: DEFAULT_WAVE_SHAPER_AMOUNT,
             interval: DEFAULT_WAVE_INTERVAL,
+            octave: DEFAULT_WAVE_OCTAVE,
+            fine_tune: DEFAULT_WAVE_FINE_TUNE,
+            unison_voices: DEFAULT_UNISON_VOICES,
+            unison_detune: DEFAULT_UNISON_DETUNE,
+            unison_width: DEFAULT_UNISON_WIDTH,
+            unison_phase_is_randomized: false,
+            shape_settings: ShapeSettings::new(),
+            sync_source: None,
+            ring_modulation_source: None,
+            fm_source: None,
+            fm_mode: FMMode::default(),
+            fm_amount: DEFAULT_FM_AMOUNT,
+            filter_send: FilterSend::default(),
+            previous_phase_offset: 0.0,
+            last_samples: (0.0, 0.0),
+            shape_change_samples: (0.0, 0.0),
+            shape_change_fade: 1.0,
         };
 
         let oscillator1 = Parameters {
-            wave: Box::new(Sine::new(sample_rate)),
+            wave: get_oscillator_wave_for_slot(
+                &WaveShape::Sine,
+                1,
+                DEFAULT_UNISON_VOICES,
+                &mut seed_generator,
+                sample_rate,
+            ),
             shape: WaveShape::Sine,
             level: DEFAULT_WAVE_LEVEL,
             drive_amount: DEFAULT_WAVE_SHAPER_AMOUNT,
             interval: DEFAULT_WAVE_INTERVAL,
+            octave: DEFAULT_WAVE_OCTAVE,
+            fine_tune: DEFAULT_WAVE_FINE_TUNE,
+            unison_voices: DEFAULT_UNISON_VOICES,
+            unison_detune: DEFAULT_UNISON_DETUNE,
+            unison_width: DEFAULT_UNISON_WIDTH,
+            unison_phase_is_randomized: false,
+            shape_settings: ShapeSettings::new(),
+            sync_source: None,
+            ring_modulation_source: None,
+            fm_source: None,
+            fm_mode: FMMode::default(),
+            fm_amount: DEFAULT_FM_AMOUNT,
+            filter_send: FilterSend::default(),
+            previous_phase_offset: 0.0,
+            last_samples: (0.0, 0.0),
+            shape_change_samples: (0.0, 0.0),
+            shape_change_fade: 1.0,
         };
 
         let oscillator2 = Parameters {
-            wave: Box::new(Sine::new(sample_rate)),
+            wave: get_oscillator_wave_for_slot(
+                &WaveShape::Sine,
+                2,
+                DEFAULT_UNISON_VOICES,
+                &mut seed_generator,
+                sample_rate,
+            ),
             shape: WaveShape::Sine,
             level: DEFAULT_WAVE_LEVEL,
             drive_amount: DEFAULT_WAVE_SHAPER_AMOUNT,
             interval: DEFAULT_WAVE_INTERVAL,
+            octave: DEFAULT_WAVE_OCTAVE,
+            fine_tune: DEFAULT_WAVE_FINE_TUNE,
+            unison_voices: DEFAULT_UNISON_VOICES,
+            unison_detune: DEFAULT_UNISON_DETUNE,
+            unison_width: DEFAULT_UNISON_WIDTH,
+            unison_phase_is_randomized: false,
+            shape_settings: ShapeSettings::new(),
+            sync_source: None,
+            ring_modulation_source: None,
+            fm_source: None,
+            fm_mode: FMMode::default(),
+            fm_amount: DEFAULT_FM_AMOUNT,
+            filter_send: FilterSend::default(),
+            previous_phase_offset: 0.0,
+            last_samples: (0.0, 0.0),
+            shape_change_samples: (0.0, 0.0),
+            shape_change_fade: 1.0,
         };
 
         let oscillator3 = Parameters {
-            wave: Box::new(Sine::new(sample_rate)),
+            wave: get_oscillator_wave_for_slot(
+                &WaveShape::Sine,
+                3,
+                DEFAULT_UNISON_VOICES,
+                &mut seed_generator,
+                sample_rate,
+            ),
             shape: WaveShape::Sine,
             level: DEFAULT_WAVE_LEVEL,
             drive_amount: DEFAULT_WAVE_SHAPER_AMOUNT,
             interval: DEFAULT_WAVE_INTERVAL,
+            octave: DEFAULT_WAVE_OCTAVE,
+            fine_tune: DEFAULT_WAVE_FINE_TUNE,
+            unison_voices: DEFAULT_UNISON_VOICES,
+            unison_detune: DEFAULT_UNISON_DETUNE,
+            unison_width: DEFAULT_UNISON_WIDTH,
+            unison_phase_is_randomized: false,
+            shape_settings: ShapeSettings::new(),
+            sync_source: None,
+            ring_modulation_source: None,
+            fm_source: None,
+            fm_mode: FMMode::default(),
+            fm_amount: DEFAULT_FM_AMOUNT,
+            filter_send: FilterSend::default(),
+            previous_phase_offset: 0.0,
+            last_samples: (0.0, 0.0),
+            shape_change_samples: (0.0, 0.0),
+            shape_change_fade: 1.0,
         };
 
         Self {
             sample_rate,
+            seed_generator,
+            quality: OscillatorQuality::default(),
+            sub_octave: DEFAULT_SUB_OCTAVE,
             oscillators: [sub_oscillator, oscillator1, oscillator2, oscillator3],
-            is_unison: false,
-            unison_frequency_offset: 0.0,
         }
     }
 
     pub fn set_oscillator_type(&mut self, wave_shape: WaveShape, oscillator_number: i32) {
-        let new_oscillator = self.get_oscillator_for_wave_shape(&wave_shape);
+        let mut new_oscillator = get_oscillator_wave_for_slot(
+            &wave_shape,
+            oscillator_number,
+            self.oscillators[oscillator_number as usize].unison_voices,
+            &mut self.seed_generator,
+            self.sample_rate,
+        );
+        self.swap_oscillator_wave(&mut new_oscillator, wave_shape, oscillator_number);
+    }
+
+    // Leaves the previous wave in `wave` so the caller decides where it gets dropped
+    pub fn swap_oscillator_wave(
+        &mut self,
+        wave: &mut Box<dyn GenerateSamples + Send + Sync>,
+        wave_shape: WaveShape,
+        oscillator_number: i32,
+    ) {
         let oscillator = &mut self.oscillators[oscillator_number as usize];
 
-        if oscillator_number == 0 {
-            oscillator.wave = Box::new(Sub::new(new_oscillator));
-        } else {
-            oscillator.wave = new_oscillator;
+        // A new shape starts from its own defaults, the same shape carries on as it was
+        if oscillator.shape != wave_shape {
+            oscillator.shape = wave_shape;
+            oscillator.shape_settings = ShapeSettings::new();
         }
 
-        oscillator.shape = wave_shape;
+        mem::swap(&mut oscillator.wave, wave);
+        oscillator.wave.set_quality(self.quality);
+        [
+            ShapeParameter::SubOctave(self.sub_octave),
+            ShapeParameter::UnisonVoices(oscillator.unison_voices),
+            ShapeParameter::UnisonDetune(oscillator.unison_detune),
+            ShapeParameter::UnisonWidth(oscillator.unison_width),
+            ShapeParameter::UnisonPhaseRandomized(oscillator.unison_phase_is_randomized),
+        ]
+        .into_iter()
+        .for_each(|parameter| oscillator.wave.set_shape_parameter(parameter));
+        oscillator.shape_settings.apply(&mut oscillator.wave);
+
+        // The new wave fades in from wherever the old one left off, rather than jumping there
+        oscillator.shape_change_samples = oscillator.last_samples;
+        oscillator.shape_change_fade = 0.0;
+    }
+
+    pub fn swap_shape_data(&mut self, shape_data: &mut ShapeData, oscillator_number: i32) {
+        self.oscillators[oscillator_number as usize]
+            .wave
+            .swap_shape_data(shape_data);
+    }
+
+    pub fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.quality = quality;
+        self.oscillators
+            .iter_mut()
+            .for_each(|oscillator| oscillator.wave.set_quality(quality));
     }
 
     pub fn set_oscillator_level(&mut self, level: f32, oscillator: i32) {
@@ -129,18 +438,148 @@ impl Oscillators {
         self.oscillators[oscillator as usize].interval = interval;
     }
 
-    pub fn set_shape_specific_parameters(&mut self, parameters: (f32, f32), oscillator: i32) {
-        self.oscillators[oscillator as usize]
+    pub fn set_oscillator_octave(&mut self, octave: i32, oscillator: i32) {
+        self.oscillators[oscillator as usize].octave =
+            octave.clamp(-MAXIMUM_OCTAVE_OFFSET, MAXIMUM_OCTAVE_OFFSET);
+    }
+
+    pub fn set_oscillator_fine_tune(&mut self, cents: f32, oscillator: i32) {
+        self.oscillators[oscillator as usize].fine_tune =
+            cents.clamp(-MAXIMUM_FINE_TUNE_CENTS, MAXIMUM_FINE_TUNE_CENTS);
+    }
+
+    pub fn set_oscillator_filter_send(&mut self, filter_send: FilterSend, oscillator: i32) {
+        self.oscillators[oscillator as usize].filter_send = filter_send;
+    }
+
+    // Builds the voices here, so this is only for rendering away from the audio thread
+    pub fn set_oscillator_unison_voices(&mut self, number_of_voices: i32, oscillator_number: i32) {
+        let oscillator = &mut self.oscillators[oscillator_number as usize];
+        oscillator.unison_voices = number_of_voices;
+
+        let mut new_oscillator = get_oscillator_wave_for_slot(
+            &oscillator.shape,
+            oscillator_number,
+            number_of_voices,
+            &mut self.seed_generator,
+            self.sample_rate,
+        );
+        let wave_shape = oscillator.shape.clone();
+        self.swap_oscillator_wave(&mut new_oscillator, wave_shape, oscillator_number);
+    }
+
+    // Leaves the previous wave in `wave` so the caller decides where it gets dropped
+    pub fn swap_unison_voices(
+        &mut self,
+        wave: &mut Box<dyn GenerateSamples + Send + Sync>,
+        number_of_voices: i32,
+        oscillator_number: i32,
+    ) {
+        let oscillator = &mut self.oscillators[oscillator_number as usize];
+        oscillator.unison_voices = number_of_voices;
+
+        let wave_shape = oscillator.shape.clone();
+        self.swap_oscillator_wave(wave, wave_shape, oscillator_number);
+    }
+
+    pub fn set_oscillator_unison_detune(&mut self, detune_cents: f32, oscillator: i32) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.unison_detune = detune_cents;
+        oscillator
             .wave
-            .set_shape_specific_parameters(parameters);
+            .set_shape_parameter(ShapeParameter::UnisonDetune(detune_cents));
+    }
+
+    pub fn set_oscillator_unison_width(&mut self, width: f32, oscillator: i32) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.unison_width = width;
+        oscillator
+            .wave
+            .set_shape_parameter(ShapeParameter::UnisonWidth(width));
+    }
+
+    pub fn set_oscillator_unison_phase_randomized(
+        &mut self,
+        is_phase_randomized: bool,
+        oscillator: i32,
+    ) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.unison_phase_is_randomized = is_phase_randomized;
+        oscillator
+            .wave
+            .set_shape_parameter(ShapeParameter::UnisonPhaseRandomized(is_phase_randomized));
+    }
+
+    // Only the sub oscillator's wave does anything with this
+    pub fn set_sub_octave(&mut self, octave: i32) {
+        self.sub_octave = octave;
+        self.oscillators.iter_mut().for_each(|oscillator| {
+            oscillator
+                .wave
+                .set_shape_parameter(ShapeParameter::SubOctave(octave));
+        });
+    }
+
+    pub fn set_shape_specific_parameters(&mut self, parameters: (f32, f32), oscillator: i32) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.shape_settings.specific_parameters = Some(parameters);
+        oscillator.wave.set_shape_specific_parameters(parameters);
+    }
+
+    pub fn set_shape_parameter(&mut self, parameter: ShapeParameter, oscillator: i32) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.shape_settings.store_parameter(parameter);
+        oscillator.wave.set_shape_parameter(parameter);
+    }
+
+    // An oscillator can't be its own source, so choosing itself turns the routing off
+    pub fn set_oscillator_sync_source(&mut self, source: Option<usize>, oscillator: i32) {
+        self.oscillators[oscillator as usize].sync_source =
+            source.filter(|source| *source != oscillator as usize);
+    }
+
+    pub fn set_oscillator_ring_modulation_source(
+        &mut self,
+        source: Option<usize>,
+        oscillator: i32,
+    ) {
+        self.oscillators[oscillator as usize].ring_modulation_source =
+            source.filter(|source| *source != oscillator as usize);
+    }
+
+    // Unlike sync and ring mod, an oscillator modulating itself is feedback FM
+    pub fn set_oscillator_fm_source(&mut self, source: Option<usize>, oscillator: i32) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.fm_source = source;
+        oscillator.previous_phase_offset = 0.0;
+    }
+
+    pub fn set_oscillator_fm_mode(&mut self, mode: FMMode, oscillator: i32) {
+        let oscillator = &mut self.oscillators[oscillator as usize];
+        oscillator.fm_mode = mode;
+        oscillator.previous_phase_offset = 0.0;
+    }
+
+    pub fn set_oscillator_fm_amount(&mut self, amount: f32, oscillator: i32) {
+        self.oscillators[oscillator as usize].fm_amount = amount.clamp(0.0, MAXIMUM_FM_AMOUNT);
     }
 
     pub fn set_oscillator_drive_amount(&mut self, amount: f32, oscillator: i32) {
         self.oscillators[oscillator as usize].drive_amount = amount;
     }
 
+    // The octave and the semitone interval, in semitones
     pub fn get_oscillator_interval(&mut self, oscillator: i32) -> i32 {
-        self.oscillators[oscillator as usize].interval
+        let oscillator = &self.oscillators[oscillator as usize];
+        oscillator.interval + oscillator.octave * SEMITONES_PER_OCTAVE
+    }
+
+    pub fn get_oscillator_fine_tune(&mut self, oscillator: i32) -> f32 {
+        self.oscillators[oscillator as usize].fine_tune
+    }
+
+    pub fn get_oscillator_filter_send(&mut self, oscillator: i32) -> FilterSend {
+        self.oscillators[oscillator as usize].filter_send
     }
 
     pub fn get_oscillator1_level(&mut self) -> f32 {
@@ -165,24 +604,25 @@ impl Oscillators {
             .for_each(|oscillator| oscillator.wave.reset());
     }
 
+    pub fn start_note(&mut self) {
+        self.oscillators
+            .iter_mut()
+            .for_each(|oscillator| oscillator.wave.start_note());
+    }
+
+    pub fn release_note(&mut self) {
+        self.oscillators
+            .iter_mut()
+            .for_each(|oscillator| oscillator.wave.release_note());
+    }
+
     pub fn get_oscillator1_next_sample(
         &mut self,
         note_frequency: f32,
         relative_level: f32,
         modulation: Option<f32>,
-    ) -> f32 {
-        if relative_level == 0.0 || note_frequency == 0.0 {
-            return 0.0;
-        }
-
-        let frequency = if self.unison_frequency_offset == 0.0 {
-            note_frequency
-        } else {
-            note_frequency - (note_frequency * self.unison_frequency_offset)
-        };
-
-        let sample = self.oscillators[1].wave.next_sample(frequency, modulation) * relative_level;
-        get_wave_shaped_sample(sample, self.oscillators[1].drive_amount)
+    ) -> (f32, f32) {
+        self.get_oscillator_next_sample(1, note_frequency, relative_level, modulation)
     }
 
     pub fn get_oscillator2_next_sample(
@@ -190,16 +630,8 @@ impl Oscillators {
         note_frequency: f32,
         relative_level: f32,
         modulation: Option<f32>,
-    ) -> f32 {
-        if relative_level == 0.0 || note_frequency == 0.0 {
-            return 0.0;
-        }
-
-        let sample = self.oscillators[2]
-            .wave
-            .next_sample(note_frequency, modulation)
-            * relative_level;
-        get_wave_shaped_sample(sample, self.oscillators[2].drive_amount)
+    ) -> (f32, f32) {
+        self.get_oscillator_next_sample(2, note_frequency, relative_level, modulation)
     }
 
     pub fn get_oscillator3_next_sample(
@@ -207,19 +639,8 @@ impl Oscillators {
         note_frequency: f32,
         relative_level: f32,
         modulation: Option<f32>,
-    ) -> f32 {
-        if relative_level == 0.0 || note_frequency == 0.0 {
-            return 0.0;
-        }
-
-        let frequency = if self.unison_frequency_offset == 0.0 {
-            note_frequency
-        } else {
-            note_frequency - (note_frequency * self.unison_frequency_offset)
-        };
-
-        let sample = self.oscillators[3].wave.next_sample(frequency, modulation) * relative_level;
-        get_wave_shaped_sample(sample, self.oscillators[3].drive_amount)
+    ) -> (f32, f32) {
+        self.get_oscillator_next_sample(3, note_frequency, relative_level, modulation)
     }
 
     pub fn get_sub_oscillator_next_sample(
@@ -227,63 +648,235 @@ impl Oscillators {
         note_frequency: f32,
         relative_level: f32,
         modulation: Option<f32>,
-    ) -> f32 {
-        if relative_level == 0.0 || note_frequency == 0.0 {
-            return 0.0;
+    ) -> (f32, f32) {
+        self.get_oscillator_next_sample(0, note_frequency, relative_level, modulation)
+    }
+
+    fn get_oscillator_next_sample(
+        &mut self,
+        oscillator: usize,
+        frequency: f32,
+        relative_level: f32,
+        modulation: Option<f32>,
+    ) -> (f32, f32) {
+        // Sync and ring mod sources keep running when muted, as they are often only there to drive another
+        let is_routing_source = self.oscillators.iter().any(|parameters| {
+            parameters.sync_source == Some(oscillator)
+                || parameters.ring_modulation_source == Some(oscillator)
+                || parameters.fm_source == Some(oscillator)
+        });
+
+        if frequency == 0.0 || (relative_level == 0.0 && !is_routing_source) {
+            self.oscillators[oscillator].last_samples = (0.0, 0.0);
+            return (0.0, 0.0);
+        }
+
+        // Sources later in the running order are heard one sample late, which is inaudible
+        if let Some(sync_source) = self.oscillators[oscillator].sync_source
+            && self.oscillators[sync_source].wave.has_phase_wrapped()
+        {
+            self.oscillators[oscillator].wave.hard_sync();
         }
 
-        let sample = self.oscillators[0]
+        let frequency = self.get_frequency_modulated_frequency(oscillator, frequency);
+        let (mut left_sample, mut right_sample) = self.oscillators[oscillator]
             .wave
-            .next_sample(note_frequency, modulation)
-            * relative_level;
-        get_wave_shaped_sample(sample, self.oscillators[0].drive_amount)
+            .next_stereo_sample(frequency, modulation);
+
+        let parameters = &mut self.oscillators[oscillator];
+        if parameters.shape_change_fade < 1.0 {
+            let (left_shape_change_sample, right_shape_change_sample) =
+                parameters.shape_change_samples;
+            left_sample = left_shape_change_sample
+                + (left_sample - left_shape_change_sample) * parameters.shape_change_fade;
+            right_sample = right_shape_change_sample
+                + (right_sample - right_shape_change_sample) * parameters.shape_change_fade;
+            parameters.shape_change_fade += 1.0 / (SHAPE_CHANGE_FADE_SECONDS * self.sample_rate);
+        }
+
+        parameters.last_samples = (left_sample, right_sample);
+
+        if let Some(ring_modulation_source) = self.oscillators[oscillator].ring_modulation_source {
+            let (left_source_sample, right_source_sample) =
+                self.oscillators[ring_modulation_source].last_samples;
+            left_sample *= left_source_sample;
+            right_sample *= right_source_sample;
+        }
+
+        get_wave_shaped_samples(
+            (left_sample * relative_level, right_sample * relative_level),
+            self.oscillators[oscillator].drive_amount,
+        )
     }
 
-    pub fn enable_unison(&mut self, unison_spread_percentage_of_note: f32) {
-        self.is_unison = true;
-        self.unison_frequency_offset = unison_spread_percentage_of_note / 2.0;
+    // The amount is a modulation index relative to the oscillator's own frequency, so the timbre
+    // holds across the keyboard
+    fn get_frequency_modulated_frequency(&mut self, oscillator: usize, frequency: f32) -> f32 {
+        let Some(fm_source) = self.oscillators[oscillator].fm_source else {
+            return frequency;
+        };
+
+        let (left_source_sample, right_source_sample) = self.oscillators[fm_source].last_samples;
+        let modulator = (left_source_sample + right_source_sample) * 0.5;
+        let parameters = &mut self.oscillators[oscillator];
+
+        match parameters.fm_mode {
+            FMMode::Linear => (frequency * (1.0 + parameters.fm_amount * modulator)).max(0.0),
+            FMMode::ThroughZero => frequency * (1.0 + parameters.fm_amount * modulator),
+            FMMode::Phase => {
+                // Shifting the frequency by how far the phase offset moved since the last sample
+                // leaves the accumulated phase offset by exactly the modulator
+                let phase_offset = parameters.fm_amount * modulator / TAU;
+                let phase_offset_change = phase_offset - parameters.previous_phase_offset;
+                parameters.previous_phase_offset = phase_offset;
+                frequency + phase_offset_change * self.sample_rate
+            }
+        }
     }
+}
 
-    pub fn disable_unison(&mut self) {
-        self.is_unison = false;
-        self.unison_frequency_offset = 0.0;
+// Anything that needs random numbers takes its seed from the generator, so the same seed always
+// builds the same waves
+pub fn get_oscillator_for_wave_shape(
+    wave_shape: &WaveShape,
+    seed_generator: &mut SmallRng,
+    sample_rate: f32,
+) -> Box<dyn GenerateSamples + Send + Sync> {
+    match wave_shape {
+        WaveShape::Noise => Box::new(Noise::new(seed_generator.random(), sample_rate)),
+        WaveShape::Pulse => Box::new(Pulse::new(sample_rate)),
+        WaveShape::Ramp => Box::new(Ramp::new(sample_rate)),
+        WaveShape::Saw => Box::new(Saw::new(sample_rate)),
+        WaveShape::Sine => Box::new(Sine::new(sample_rate)),
+        WaveShape::Square => Box::new(Square::new(sample_rate)),
+        WaveShape::SuperSaw => Box::new(SuperSaw::new(seed_generator.random(), sample_rate)),
+        WaveShape::Triangle => Box::new(Triangle::new(sample_rate)),
+        WaveShape::FM => Box::new(FM::new(sample_rate)),
+        WaveShape::AM => Box::new(AM::new(sample_rate)),
+        WaveShape::Wavetable => Box::new(Wavetable::new(sample_rate)),
+        WaveShape::FourOperatorFM => Box::new(FourOperatorFM::new(sample_rate)),
+        WaveShape::Pluck => Box::new(Pluck::new(seed_generator.random(), sample_rate)),
+        WaveShape::Additive => Box::new(Additive::new(sample_rate)),
+        WaveShape::Sampler => Box::new(Sampler::new(sample_rate)),
+        WaveShape::Morph => Box::new(Morph::new(sample_rate)),
     }
+}
 
-    pub fn get_oscillator_for_wave_shape(
-        &mut self,
-        wave_shape: &WaveShape,
-    ) -> Box<dyn GenerateSamples + Send + Sync> {
-        match wave_shape {
-            WaveShape::Noise => Box::new(Noise::new()),
-            WaveShape::Pulse => Box::new(Pulse::new(self.sample_rate)),
-            WaveShape::Ramp => Box::new(Ramp::new(self.sample_rate)),
-            WaveShape::Saw => Box::new(Saw::new(self.sample_rate)),
-            WaveShape::Sine => Box::new(Sine::new(self.sample_rate)),
-            WaveShape::Square => Box::new(Square::new(self.sample_rate)),
-            WaveShape::SuperSaw => Box::new(SuperSaw::new(self.sample_rate)),
-            WaveShape::Triangle => Box::new(Triangle::new(self.sample_rate)),
-            WaveShape::FM => Box::new(FM::new(self.sample_rate)),
-            WaveShape::AM => Box::new(AM::new(self.sample_rate)),
-        }
+// Every slot stacks its waves for unison, and the sub oscillator slot wraps them in a sub
+pub fn get_oscillator_wave_for_slot(
+    wave_shape: &WaveShape,
+    oscillator_number: i32,
+    unison_voices: i32,
+    seed_generator: &mut SmallRng,
+    sample_rate: f32,
+) -> Box<dyn GenerateSamples + Send + Sync> {
+    let voices = (0..unison::get_number_of_unison_voices(unison_voices))
+        .map(|_| get_oscillator_for_wave_shape(wave_shape, seed_generator, sample_rate))
+        .collect();
+    let wave: Box<dyn GenerateSamples + Send + Sync> =
+        Box::new(Unison::new(voices, seed_generator.random()));
+
+    if oscillator_number == 0 {
+        Box::new(Sub::new(wave))
+    } else {
+        wave
     }
+}
 
-    pub fn get_wave_shape_from_shape_name(&self, wave_shape: SharedString) -> WaveShape {
-        match wave_shape.as_str() {
-            "Noise" => WaveShape::Noise,
-            "Pulse" => WaveShape::Pulse,
-            "Ramp" => WaveShape::Ramp,
-            "Saw" => WaveShape::Saw,
-            "Sine" => WaveShape::Sine,
-            "Square" => WaveShape::Square,
-            "SuperSaw" => WaveShape::SuperSaw,
-            "Triangle" => WaveShape::Triangle,
-            "FM" => WaveShape::FM,
-            "AM" => WaveShape::AM,
-            _ => WaveShape::Sine,
-        }
+// Each setting takes over from the last one like it, and every 4 op FM operator parameter is kept
+// apart. The sub and unison settings are kept by the oscillator itself
+fn get_shape_setting_slot(parameter: &ShapeParameter) -> Option<usize> {
+    match parameter {
+        ShapeParameter::SuperSawVoices(_) => Some(0),
+        ShapeParameter::FMAlgorithm(_) => Some(1),
+        ShapeParameter::AdditivePreset(_) => Some(2),
+        ShapeParameter::AdditiveStretch(_) => Some(3),
+        ShapeParameter::SamplerRootFrequency(_) => Some(4),
+        ShapeParameter::SamplerLoopMode(_) => Some(5),
+        ShapeParameter::SamplerInterpolation(_) => Some(6),
+        ShapeParameter::SuperSawStereoSpread(_) => Some(7),
+        ShapeParameter::FMOperator(operator, operator_parameter, _) => usize::try_from(*operator)
+            .ok()
+            .filter(|operator| *operator < NUMBER_OF_OPERATORS)
+            .map(|operator| {
+                NUMBER_OF_SHAPE_SETTINGS_BEFORE_FM_OPERATORS
+                    + operator * NUMBER_OF_OPERATOR_PARAMETERS
+                    + *operator_parameter as usize
+            }),
+        ShapeParameter::SubOctave(_)
+        | ShapeParameter::UnisonVoices(_)
+        | ShapeParameter::UnisonDetune(_)
+        | ShapeParameter::UnisonWidth(_)
+        | ShapeParameter::UnisonPhaseRandomized(_) => None,
     }
 }
 
+pub fn get_oscillator_quality_from_quality_name(quality_name: &str) -> OscillatorQuality {
+    match quality_name {
+        "Naive" => OscillatorQuality::Naive,
+        "Band Limited" => OscillatorQuality::BandLimited,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_wave_shape_from_shape_name(wave_shape: &str) -> WaveShape {
+    match wave_shape {
+        "Noise" => WaveShape::Noise,
+        "Pulse" => WaveShape::Pulse,
+        "Ramp" => WaveShape::Ramp,
+        "Saw" => WaveShape::Saw,
+        "Sine" => WaveShape::Sine,
+        "Square" => WaveShape::Square,
+        "SuperSaw" => WaveShape::SuperSaw,
+        "Triangle" => WaveShape::Triangle,
+        "FM" => WaveShape::FM,
+        "AM" => WaveShape::AM,
+        "Wavetable" => WaveShape::Wavetable,
+        "4 Op FM" => WaveShape::FourOperatorFM,
+        "Pluck" => WaveShape::Pluck,
+        "Additive" => WaveShape::Additive,
+        "Sampler" => WaveShape::Sampler,
+        "Morph" => WaveShape::Morph,
+        _ => WaveShape::Sine,
+    }
+}
+
+// Phase runs over 0..1 per cycle and wraps, so it never loses precision however long a note plays
+pub fn get_next_phase(phase: f32, phase_increment: f32) -> f32 {
+    (phase + phase_increment).rem_euclid(1.0)
+}
+
+// Catches wraps in either direction, as long as the phase moves less than half a cycle per sample
+pub fn did_phase_wrap(previous_phase: f32, next_phase: f32) -> bool {
+    (next_phase - previous_phase).abs() > 0.5
+}
+
+pub fn get_fm_mode_from_mode_name(mode_name: &str) -> FMMode {
+    match mode_name {
+        "Linear" => FMMode::Linear,
+        "Through Zero" => FMMode::ThroughZero,
+        "Phase" => FMMode::Phase,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_oscillator_index_from_source_name(source_name: &str) -> Option<usize> {
+    match source_name {
+        "Sub" => Some(0),
+        "Osc 1" => Some(1),
+        "Osc 2" => Some(2),
+        "Osc 3" => Some(3),
+        _ => None,
+    }
+}
+
+fn get_wave_shaped_samples(samples: (f32, f32), amount: f32) -> (f32, f32) {
+    (
+        get_wave_shaped_sample(samples.0, amount),
+        get_wave_shaped_sample(samples.1, amount),
+    )
+}
+
 fn get_wave_shaped_sample(sample: f32, amount: f32) -> f32 {
     if amount == 0.0 {
         return sample;
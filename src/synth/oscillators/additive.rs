@@ -0,0 +1,246 @@
+use crate::synth::oscillators::{GenerateSamples, ShapeParameter, did_phase_wrap, get_next_phase};
+use std::f32::consts::TAU;
+
+const MAXIMUM_NUMBER_OF_PARTIALS: usize = 64;
+const DEFAULT_TILT: f32 = 0.0;
+const DEFAULT_ODD_EVEN_BALANCE: f32 = 0.5;
+const DEFAULT_STRETCH: f32 = 0.0;
+const MAXIMUM_TILT: f32 = 1.0;
+const MAXIMUM_STRETCH: f32 = 0.1;
+
+// Partials fade out over the last tenth of the band below Nyquist, so a gliding note doesn't click
+// as its top partials drop out
+const NYQUIST_FADE_PROPORTION: f32 = 0.1;
+
+// The level of a full scale saw, so every preset plays about as loud
+const TARGET_SUM_OF_SQUARED_AMPLITUDES: f32 = 2.0 / 3.0;
+
+// Hammond drawbar pitches as multiples of the 8' fundamental, in drawbar order: 16', 5 1/3', 8',
+// 4', 2 2/3', 2', 1 3/5', 1 1/3' and 1'
+const DRAWBAR_RATIOS: [f32; 9] = [0.5, 1.5, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0];
+const DRAWBAR_REGISTRATION: [u8; 9] = [8, 8, 8, 0, 0, 0, 0, 0, 0];
+const MAXIMUM_DRAWBAR_SETTING: u8 = 8;
+const DECIBELS_PER_DRAWBAR_STEP: f32 = 3.0;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum AdditivePreset {
+    #[default]
+    Saw,
+    Square,
+    Organ,
+}
+
+pub struct Additive {
+    phases: [f32; MAXIMUM_NUMBER_OF_PARTIALS],
+    ratios: [f32; MAXIMUM_NUMBER_OF_PARTIALS],
+    preset_levels: [f32; MAXIMUM_NUMBER_OF_PARTIALS],
+    stretched_ratios: [f32; MAXIMUM_NUMBER_OF_PARTIALS],
+    levels: [f32; MAXIMUM_NUMBER_OF_PARTIALS],
+    number_of_partials: usize,
+    tilt: f32,
+    odd_even_balance: f32,
+    stretch: f32,
+    phase_has_wrapped: bool,
+    sample_rate: f32,
+}
+
+impl Additive {
+    pub fn new(sample_rate: f32) -> Self {
+        let mut additive = Self {
+            phases: [0.0; MAXIMUM_NUMBER_OF_PARTIALS],
+            ratios: [0.0; MAXIMUM_NUMBER_OF_PARTIALS],
+            preset_levels: [0.0; MAXIMUM_NUMBER_OF_PARTIALS],
+            stretched_ratios: [0.0; MAXIMUM_NUMBER_OF_PARTIALS],
+            levels: [0.0; MAXIMUM_NUMBER_OF_PARTIALS],
+            number_of_partials: 0,
+            tilt: DEFAULT_TILT,
+            odd_even_balance: DEFAULT_ODD_EVEN_BALANCE,
+            stretch: DEFAULT_STRETCH,
+            phase_has_wrapped: false,
+            sample_rate,
+        };
+
+        additive.load_preset(AdditivePreset::default());
+        additive.update_partials();
+        additive
+    }
+
+    fn load_preset(&mut self, preset: AdditivePreset) {
+        self.ratios = [0.0; MAXIMUM_NUMBER_OF_PARTIALS];
+        self.preset_levels = [0.0; MAXIMUM_NUMBER_OF_PARTIALS];
+
+        match preset {
+            AdditivePreset::Saw => {
+                self.number_of_partials = MAXIMUM_NUMBER_OF_PARTIALS;
+                for (harmonic, (ratio, level)) in self
+                    .ratios
+                    .iter_mut()
+                    .zip(self.preset_levels.iter_mut())
+                    .enumerate()
+                {
+                    *ratio = (harmonic + 1) as f32;
+                    *level = 1.0 / *ratio;
+                }
+            }
+            AdditivePreset::Square => {
+                self.number_of_partials = MAXIMUM_NUMBER_OF_PARTIALS;
+                for (harmonic, (ratio, level)) in self
+                    .ratios
+                    .iter_mut()
+                    .zip(self.preset_levels.iter_mut())
+                    .enumerate()
+                {
+                    *ratio = (harmonic + 1) as f32;
+                    *level = if harmonic.is_multiple_of(2) {
+                        1.0 / *ratio
+                    } else {
+                        0.0
+                    };
+                }
+            }
+            AdditivePreset::Organ => {
+                self.number_of_partials = DRAWBAR_RATIOS.len();
+                self.ratios[..DRAWBAR_RATIOS.len()].copy_from_slice(&DRAWBAR_RATIOS);
+                for (level, setting) in self.preset_levels.iter_mut().zip(DRAWBAR_REGISTRATION) {
+                    *level = get_drawbar_level(setting);
+                }
+            }
+        }
+    }
+
+    // Tilt, balance and stretch are applied on top of the preset, then everything is scaled back
+    // to the level of a saw
+    fn update_partials(&mut self) {
+        let odd_level = (2.0 * (1.0 - self.odd_even_balance)).min(1.0);
+        let even_level = (2.0 * self.odd_even_balance).min(1.0);
+        let exponent = 1.0 + self.stretch;
+        self.levels = [0.0; MAXIMUM_NUMBER_OF_PARTIALS];
+
+        let partials = self
+            .levels
+            .iter_mut()
+            .zip(self.stretched_ratios.iter_mut())
+            .zip(self.ratios.iter().zip(self.preset_levels))
+            .take(self.number_of_partials);
+
+        for ((level, stretched_ratio), (ratio, preset_level)) in partials {
+            let balance_level = if is_even_harmonic(*ratio) {
+                even_level
+            } else {
+                odd_level
+            };
+
+            *level = preset_level * ratio.powf(self.tilt) * balance_level;
+            *stretched_ratio = ratio.powf(exponent);
+        }
+
+        let sum_of_squared_levels: f32 = self.levels.iter().map(|level| level * level).sum();
+        if sum_of_squared_levels > 0.0 {
+            let normalization = (TARGET_SUM_OF_SQUARED_AMPLITUDES / sum_of_squared_levels).sqrt();
+            self.levels
+                .iter_mut()
+                .for_each(|level| *level *= normalization);
+        }
+    }
+}
+
+impl GenerateSamples for Additive {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let nyquist = self.sample_rate / 2.0;
+        let fade_start = nyquist * (1.0 - NYQUIST_FADE_PROPORTION);
+        let mut sample = 0.0;
+
+        let partials = self
+            .phases
+            .iter_mut()
+            .zip(self.stretched_ratios)
+            .zip(self.levels)
+            .take(self.number_of_partials)
+            .enumerate();
+
+        for (partial, ((phase, ratio), level)) in partials {
+            let partial_frequency = new_frequency * ratio;
+            let absolute_frequency = partial_frequency.abs();
+
+            if level != 0.0 && absolute_frequency < nyquist {
+                let fade_level = if absolute_frequency > fade_start {
+                    (nyquist - absolute_frequency) / (nyquist - fade_start)
+                } else {
+                    1.0
+                };
+                sample += (TAU * *phase).sin() * level * fade_level;
+            }
+
+            // Every partial keeps running, so they are still in step when a cut partial comes back
+            let next_phase = get_next_phase(*phase, partial_frequency / self.sample_rate);
+            if partial == 0 {
+                self.phase_has_wrapped = did_phase_wrap(*phase, next_phase);
+            }
+            *phase = next_phase;
+        }
+
+        sample
+    }
+
+    // Brightness tilt, then the balance between the odd and even harmonics
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.tilt = parameters.0.clamp(-MAXIMUM_TILT, MAXIMUM_TILT);
+        self.odd_even_balance = parameters.1.clamp(0.0, 1.0);
+        self.update_partials();
+    }
+
+    fn reset(&mut self) {
+        self.phases = [0.0; MAXIMUM_NUMBER_OF_PARTIALS];
+    }
+
+    // Each partial moves on by its own ratio, so the whole wave shifts along without changing shape
+    fn set_phase(&mut self, phase: f32) {
+        for (partial_phase, ratio) in self.phases.iter_mut().zip(self.stretched_ratios) {
+            *partial_phase = (phase * ratio).rem_euclid(1.0);
+        }
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    // The stretch spreads the partials out like a stiff piano string, or squeezes them together
+    fn set_shape_parameter(&mut self, parameter: ShapeParameter) {
+        match parameter {
+            ShapeParameter::AdditivePreset(preset) => {
+                self.load_preset(preset);
+                self.update_partials();
+            }
+            ShapeParameter::AdditiveStretch(stretch) => {
+                self.stretch = stretch.clamp(-MAXIMUM_STRETCH, MAXIMUM_STRETCH);
+                self.update_partials();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn get_additive_preset_from_preset_name(preset_name: &str) -> AdditivePreset {
+    match preset_name {
+        "Saw" => AdditivePreset::Saw,
+        "Square" => AdditivePreset::Square,
+        "Organ" => AdditivePreset::Organ,
+        _ => Default::default(),
+    }
+}
+
+// Each drawbar step is about 3 dB, with 0 pushed all the way in
+fn get_drawbar_level(setting: u8) -> f32 {
+    if setting == 0 {
+        return 0.0;
+    }
+
+    let steps_down = (MAXIMUM_DRAWBAR_SETTING - setting.min(MAXIMUM_DRAWBAR_SETTING)) as f32;
+    10.0f32.powf(-steps_down * DECIBELS_PER_DRAWBAR_STEP / 20.0)
+}
+
+// The organ's sub and fifth drawbars aren't harmonics of the fundamental, so they count as odd
+fn is_even_harmonic(ratio: f32) -> bool {
+    ratio.fract() == 0.0 && (ratio as u32).is_multiple_of(2)
+}
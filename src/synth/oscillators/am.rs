@@ -35,4 +35,8 @@ impl GenerateSamples for AM {
         self.carrier.reset();
         self.modulator.reset();
     }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.carrier.has_phase_wrapped()
+    }
 }
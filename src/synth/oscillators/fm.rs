@@ -40,4 +40,8 @@ impl GenerateSamples for FM {
         self.carrier.reset();
         self.modulator.reset();
     }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.carrier.has_phase_wrapped()
+    }
 }
@@ -0,0 +1,374 @@
+use crate::synth::oscillators::{GenerateSamples, ShapeParameter, did_phase_wrap, get_next_phase};
+use std::f32::consts::PI;
+
+pub const NUMBER_OF_OPERATORS: usize = 4;
+pub const NUMBER_OF_OPERATOR_PARAMETERS: usize = 8;
+const NUMBER_OF_ALGORITHMS: usize = 8;
+const DEFAULT_ALGORITHM: usize = 0;
+const DEFAULT_RATIO: f32 = 1.0;
+const DEFAULT_FIXED_FREQUENCY: f32 = 0.0;
+const DEFAULT_CARRIER_LEVEL: f32 = 1.0;
+const DEFAULT_MODULATOR_LEVEL: f32 = 0.0;
+const DEFAULT_FEEDBACK: f32 = 0.0;
+const DEFAULT_ATTACK_MILLISECONDS: f32 = 5.0;
+const DEFAULT_DECAY_MILLISECONDS: f32 = 300.0;
+const DEFAULT_SUSTAIN_LEVEL: f32 = 1.0;
+const DEFAULT_RELEASE_MILLISECONDS: f32 = 200.0;
+const MINIMUM_RATIO: f32 = 0.5;
+const MAXIMUM_RATIO: f32 = 32.0;
+const MAXIMUM_FIXED_FREQUENCY: f32 = 10_000.0;
+const MAXIMUM_ENVELOPE_MILLISECONDS: f32 = 10_000.0;
+
+// Phase offsets are in cycles. A full level modulator sweeps its target by 13 radians, about where
+// a DX7 operator at output level 99 ends up
+const MAXIMUM_MODULATION_DEPTH: f32 = 13.0 / (2.0 * PI);
+const MAXIMUM_FEEDBACK_DEPTH: f32 = 0.5;
+
+// Decays and releases are exponential, reaching 1% of the way back after the set time
+const ENVELOPE_TIME_CONSTANTS_PER_STAGE: f32 = 4.6;
+const ENVELOPE_SILENCE_LEVEL: f32 = 0.0001;
+
+// The eight DX9/TX81Z algorithms. Each operator lists the operators modulating it, and higher
+// numbered operators only ever modulate lower numbered ones, so running them from operator 4 down
+// to operator 1 has every modulator ready before its target
+struct Algorithm {
+    modulators: [&'static [usize]; NUMBER_OF_OPERATORS],
+    carriers: &'static [usize],
+}
+
+const ALGORITHMS: [Algorithm; NUMBER_OF_ALGORITHMS] = [
+    // 4 > 3 > 2 > 1
+    Algorithm {
+        modulators: [&[1], &[2], &[3], &[]],
+        carriers: &[0],
+    },
+    // (3 + 4) > 2 > 1
+    Algorithm {
+        modulators: [&[1], &[2, 3], &[], &[]],
+        carriers: &[0],
+    },
+    // (3 > 2 + 4) > 1
+    Algorithm {
+        modulators: [&[1, 3], &[2], &[], &[]],
+        carriers: &[0],
+    },
+    // (2 + 4 > 3) > 1
+    Algorithm {
+        modulators: [&[1, 2], &[], &[3], &[]],
+        carriers: &[0],
+    },
+    // 2 > 1, 4 > 3
+    Algorithm {
+        modulators: [&[1], &[], &[3], &[]],
+        carriers: &[0, 2],
+    },
+    // 4 > 1, 4 > 2, 4 > 3
+    Algorithm {
+        modulators: [&[3], &[3], &[3], &[]],
+        carriers: &[0, 1, 2],
+    },
+    // 4 > 3, with 1 and 2 alone
+    Algorithm {
+        modulators: [&[], &[], &[3], &[]],
+        carriers: &[0, 1, 2],
+    },
+    // All four side by side
+    Algorithm {
+        modulators: [&[], &[], &[], &[]],
+        carriers: &[0, 1, 2, 3],
+    },
+];
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum OperatorParameter {
+    #[default]
+    Ratio,
+    FixedFrequency,
+    Level,
+    Feedback,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+enum EnvelopeStage {
+    #[default]
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+struct OperatorEnvelope {
+    stage: EnvelopeStage,
+    level: f32,
+    attack_increment: f32,
+    decay_coefficient: f32,
+    sustain_level: f32,
+    release_coefficient: f32,
+    sample_rate: f32,
+}
+
+impl OperatorEnvelope {
+    fn new(sample_rate: f32) -> Self {
+        Self {
+            stage: EnvelopeStage::default(),
+            level: 0.0,
+            attack_increment: get_attack_increment(DEFAULT_ATTACK_MILLISECONDS, sample_rate),
+            decay_coefficient: get_exponential_coefficient(DEFAULT_DECAY_MILLISECONDS, sample_rate),
+            sustain_level: DEFAULT_SUSTAIN_LEVEL,
+            release_coefficient: get_exponential_coefficient(
+                DEFAULT_RELEASE_MILLISECONDS,
+                sample_rate,
+            ),
+            sample_rate,
+        }
+    }
+
+    // Attacks start from wherever the level is, so retriggering a sounding note doesn't click
+    fn start(&mut self) {
+        self.stage = EnvelopeStage::Attack;
+    }
+
+    fn release(&mut self) {
+        if self.stage != EnvelopeStage::Idle {
+            self.stage = EnvelopeStage::Release;
+        }
+    }
+
+    fn next_level(&mut self) -> f32 {
+        match self.stage {
+            EnvelopeStage::Idle => {}
+            EnvelopeStage::Attack => {
+                self.level += self.attack_increment;
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = EnvelopeStage::Decay;
+                }
+            }
+            EnvelopeStage::Decay => {
+                self.level =
+                    self.sustain_level + (self.level - self.sustain_level) * self.decay_coefficient;
+                if (self.level - self.sustain_level).abs() < ENVELOPE_SILENCE_LEVEL {
+                    self.level = self.sustain_level;
+                    self.stage = EnvelopeStage::Sustain;
+                }
+            }
+            EnvelopeStage::Sustain => self.level = self.sustain_level,
+            EnvelopeStage::Release => {
+                self.level *= self.release_coefficient;
+                if self.level < ENVELOPE_SILENCE_LEVEL {
+                    self.level = 0.0;
+                    self.stage = EnvelopeStage::Idle;
+                }
+            }
+        }
+
+        self.level
+    }
+
+    fn set_attack_milliseconds(&mut self, milliseconds: f32) {
+        self.attack_increment = get_attack_increment(milliseconds, self.sample_rate);
+    }
+
+    fn set_decay_milliseconds(&mut self, milliseconds: f32) {
+        self.decay_coefficient = get_exponential_coefficient(milliseconds, self.sample_rate);
+    }
+
+    fn set_sustain_level(&mut self, level: f32) {
+        self.sustain_level = level.clamp(0.0, 1.0);
+    }
+
+    fn set_release_milliseconds(&mut self, milliseconds: f32) {
+        self.release_coefficient = get_exponential_coefficient(milliseconds, self.sample_rate);
+    }
+}
+
+struct Operator {
+    phase: f32,
+    ratio: f32,
+    fixed_frequency: f32,
+    level: f32,
+    feedback: f32,
+    previous_outputs: (f32, f32),
+    envelope: OperatorEnvelope,
+}
+
+impl Operator {
+    fn new(level: f32, sample_rate: f32) -> Self {
+        Self {
+            phase: 0.0,
+            ratio: DEFAULT_RATIO,
+            fixed_frequency: DEFAULT_FIXED_FREQUENCY,
+            level,
+            feedback: DEFAULT_FEEDBACK,
+            previous_outputs: (0.0, 0.0),
+            envelope: OperatorEnvelope::new(sample_rate),
+        }
+    }
+
+    // A fixed frequency of zero means the operator follows the note at its ratio
+    fn get_frequency(&self, tone_frequency: f32) -> f32 {
+        if self.fixed_frequency > 0.0 {
+            self.fixed_frequency
+        } else {
+            tone_frequency * self.ratio
+        }
+    }
+
+    fn next_sample(&mut self, tone_frequency: f32, phase_modulation: f32, sample_rate: f32) -> f32 {
+        // Averaging the last two outputs keeps high feedback from breaking into buzzing, as on the DX7
+        let feedback = (self.previous_outputs.0 + self.previous_outputs.1)
+            * 0.5
+            * self.feedback
+            * MAXIMUM_FEEDBACK_DEPTH;
+
+        let modulated_phase = self.phase + phase_modulation + feedback;
+        let output = (2.0 * PI * modulated_phase).sin() * self.level * self.envelope.next_level();
+
+        self.previous_outputs = (output, self.previous_outputs.0);
+        self.phase = get_next_phase(self.phase, self.get_frequency(tone_frequency) / sample_rate);
+        output
+    }
+
+    fn set_parameter(&mut self, parameter: OperatorParameter, value: f32) {
+        match parameter {
+            OperatorParameter::Ratio => self.ratio = value.clamp(MINIMUM_RATIO, MAXIMUM_RATIO),
+            OperatorParameter::FixedFrequency => {
+                self.fixed_frequency = value.clamp(0.0, MAXIMUM_FIXED_FREQUENCY);
+            }
+            OperatorParameter::Level => self.level = value.clamp(0.0, 1.0),
+            OperatorParameter::Feedback => self.feedback = value.clamp(0.0, 1.0),
+            OperatorParameter::Attack => self.envelope.set_attack_milliseconds(value),
+            OperatorParameter::Decay => self.envelope.set_decay_milliseconds(value),
+            OperatorParameter::Sustain => self.envelope.set_sustain_level(value),
+            OperatorParameter::Release => self.envelope.set_release_milliseconds(value),
+        }
+    }
+}
+
+pub struct FourOperatorFM {
+    operators: [Operator; NUMBER_OF_OPERATORS],
+    algorithm: usize,
+    phase_has_wrapped: bool,
+    sample_rate: f32,
+}
+
+impl FourOperatorFM {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            operators: [
+                Operator::new(DEFAULT_CARRIER_LEVEL, sample_rate),
+                Operator::new(DEFAULT_MODULATOR_LEVEL, sample_rate),
+                Operator::new(DEFAULT_MODULATOR_LEVEL, sample_rate),
+                Operator::new(DEFAULT_MODULATOR_LEVEL, sample_rate),
+            ],
+            algorithm: DEFAULT_ALGORITHM,
+            phase_has_wrapped: false,
+            sample_rate,
+        }
+    }
+}
+
+impl GenerateSamples for FourOperatorFM {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let algorithm = &ALGORITHMS[self.algorithm];
+        let mut outputs = [0.0; NUMBER_OF_OPERATORS];
+        let previous_phase = self.operators[0].phase;
+
+        for operator in (0..NUMBER_OF_OPERATORS).rev() {
+            let phase_modulation: f32 = algorithm.modulators[operator]
+                .iter()
+                .map(|modulator| outputs[*modulator])
+                .sum::<f32>()
+                * MAXIMUM_MODULATION_DEPTH;
+
+            outputs[operator] = self.operators[operator].next_sample(
+                new_frequency,
+                phase_modulation,
+                self.sample_rate,
+            );
+        }
+
+        self.phase_has_wrapped = did_phase_wrap(previous_phase, self.operators[0].phase);
+
+        let carrier_sum: f32 = algorithm
+            .carriers
+            .iter()
+            .map(|carrier| outputs[*carrier])
+            .sum();
+        carrier_sum / algorithm.carriers.len() as f32
+    }
+
+    fn set_shape_specific_parameters(&mut self, _parameters: (f32, f32)) {}
+
+    fn reset(&mut self) {
+        self.operators.iter_mut().for_each(|operator| {
+            operator.phase = 0.0;
+            operator.previous_outputs = (0.0, 0.0);
+        });
+    }
+
+    // Operator 1 is a carrier in every algorithm, so its cycle is the one that is heard
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    fn start_note(&mut self) {
+        self.operators
+            .iter_mut()
+            .for_each(|operator| operator.envelope.start());
+    }
+
+    fn release_note(&mut self) {
+        self.operators
+            .iter_mut()
+            .for_each(|operator| operator.envelope.release());
+    }
+
+    fn set_shape_parameter(&mut self, parameter: ShapeParameter) {
+        match parameter {
+            ShapeParameter::FMAlgorithm(algorithm) => {
+                self.algorithm = ((algorithm.max(1) - 1) as usize).min(NUMBER_OF_ALGORITHMS - 1);
+            }
+            ShapeParameter::FMOperator(operator, parameter, value) => {
+                if let Some(operator) = self.operators.get_mut(operator as usize) {
+                    operator.set_parameter(parameter, value);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn get_operator_parameter_from_parameter_name(parameter_name: &str) -> OperatorParameter {
+    match parameter_name {
+        "Ratio" => OperatorParameter::Ratio,
+        "Fixed Frequency" => OperatorParameter::FixedFrequency,
+        "Level" => OperatorParameter::Level,
+        "Feedback" => OperatorParameter::Feedback,
+        "Attack" => OperatorParameter::Attack,
+        "Decay" => OperatorParameter::Decay,
+        "Sustain" => OperatorParameter::Sustain,
+        "Release" => OperatorParameter::Release,
+        _ => Default::default(),
+    }
+}
+
+fn get_attack_increment(milliseconds: f32, sample_rate: f32) -> f32 {
+    let samples = get_number_of_samples(milliseconds, sample_rate);
+    1.0 / samples
+}
+
+fn get_exponential_coefficient(milliseconds: f32, sample_rate: f32) -> f32 {
+    let samples = get_number_of_samples(milliseconds, sample_rate);
+    (-ENVELOPE_TIME_CONSTANTS_PER_STAGE / samples).exp()
+}
+
+fn get_number_of_samples(milliseconds: f32, sample_rate: f32) -> f32 {
+    (milliseconds.clamp(0.0, MAXIMUM_ENVELOPE_MILLISECONDS) / 1000.0 * sample_rate).max(1.0)
+}
@@ -0,0 +1,153 @@
+use crate::synth::oscillators::poly_blep::poly_blep;
+use crate::synth::oscillators::{
+    GenerateSamples, OscillatorQuality, did_phase_wrap, get_next_phase,
+};
+use std::f32::consts::TAU;
+
+const DEFAULT_PHASE: f32 = 0.0;
+const DEFAULT_MORPH: f32 = 0.0;
+const DEFAULT_PHASE_DISTORTION: f32 = 0.0;
+const MODULATION_CENTER_VALUE: f32 = 1.0;
+const HALF_CYCLE: f32 = 0.5;
+const NARROWEST_PULSE_WIDTH: f32 = 0.1;
+
+// Sine, triangle, saw, square and pulse, with the morph sweeping through the gaps between them
+const NUMBER_OF_MORPH_STAGES: f32 = 4.0;
+
+// How far the CZ knee can move from the middle of the cycle. Any closer to the start and the first
+// half of the cycle becomes a step
+const MAXIMUM_KNEE_OFFSET: f32 = 0.48;
+
+// Parameter changes glide over a few milliseconds, so stepping a slider doesn't click
+const PARAMETER_SMOOTHING_SECONDS: f32 = 0.005;
+
+pub struct Morph {
+    phase: f32,
+    phase_has_wrapped: bool,
+    morph: f32,
+    phase_distortion: f32,
+    target_morph: f32,
+    target_phase_distortion: f32,
+    smoothing_coefficient: f32,
+    sample_rate: f32,
+    quality: OscillatorQuality,
+}
+
+impl Morph {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            phase: DEFAULT_PHASE,
+            phase_has_wrapped: false,
+            morph: DEFAULT_MORPH,
+            phase_distortion: DEFAULT_PHASE_DISTORTION,
+            target_morph: DEFAULT_MORPH,
+            target_phase_distortion: DEFAULT_PHASE_DISTORTION,
+            smoothing_coefficient: 1.0 - (-1.0 / (PARAMETER_SMOOTHING_SECONDS * sample_rate)).exp(),
+            sample_rate,
+            quality: OscillatorQuality::default(),
+        }
+    }
+
+    fn get_stage_sample(&self, stage: usize, phase: f32, phase_increment: f32) -> f32 {
+        match stage {
+            0 => (TAU * phase).sin(),
+            1 => 1.0 - 4.0 * (phase - HALF_CYCLE).abs(),
+            2 => {
+                let mut sample = 2.0 * phase - 1.0;
+                if self.quality == OscillatorQuality::BandLimited {
+                    sample -= poly_blep(phase, phase_increment);
+                }
+                sample
+            }
+            _ => self.get_pulse_sample(phase, phase_increment, HALF_CYCLE),
+        }
+    }
+
+    // Centered on zero and kept within full scale, so narrowing the pulse doesn't shift the whole
+    // wave up or down
+    fn get_pulse_sample(&self, phase: f32, phase_increment: f32, width: f32) -> f32 {
+        let mut sample = if phase < width { 1.0 } else { -1.0 };
+
+        if self.quality == OscillatorQuality::BandLimited {
+            let falling_edge_phase = (phase + 1.0 - width).rem_euclid(1.0);
+            sample += poly_blep(phase, phase_increment);
+            sample -= poly_blep(falling_edge_phase, phase_increment);
+        }
+
+        let offset = 2.0 * width - 1.0;
+        (sample - offset) / (1.0 + offset.abs())
+    }
+}
+
+impl GenerateSamples for Morph {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        self.morph += (self.target_morph - self.morph) * self.smoothing_coefficient;
+        self.phase_distortion +=
+            (self.target_phase_distortion - self.phase_distortion) * self.smoothing_coefficient;
+
+        let modulation_offset =
+            modulation.map_or(0.0, |modulation| modulation - MODULATION_CENTER_VALUE);
+        let morph = (self.morph + modulation_offset).clamp(0.0, 1.0) * NUMBER_OF_MORPH_STAGES;
+        let phase_distortion = (self.phase_distortion + modulation_offset).clamp(0.0, 1.0);
+
+        let phase_increment = tone_frequency / self.sample_rate;
+        let (distorted_phase, phase_slope) = get_distorted_phase(self.phase, phase_distortion);
+        let distorted_phase_increment = phase_increment * phase_slope;
+
+        // The last stage is the pulse, which narrows as the morph moves along it
+        let stage = (morph as usize).min(NUMBER_OF_MORPH_STAGES as usize - 1);
+        let stage_fraction = morph - stage as f32;
+        let sample = if stage == NUMBER_OF_MORPH_STAGES as usize - 1 {
+            let width = HALF_CYCLE - (HALF_CYCLE - NARROWEST_PULSE_WIDTH) * stage_fraction;
+            self.get_pulse_sample(distorted_phase, distorted_phase_increment, width)
+        } else {
+            let current_sample =
+                self.get_stage_sample(stage, distorted_phase, distorted_phase_increment);
+            let next_sample =
+                self.get_stage_sample(stage + 1, distorted_phase, distorted_phase_increment);
+            current_sample + (next_sample - current_sample) * stage_fraction
+        };
+
+        let next_phase = get_next_phase(self.phase, phase_increment);
+        self.phase_has_wrapped = did_phase_wrap(self.phase, next_phase);
+        self.phase = next_phase;
+        sample
+    }
+
+    // The morph position, then the amount of phase distortion
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.target_morph = parameters.0.clamp(0.0, 1.0);
+        self.target_phase_distortion = parameters.1.clamp(0.0, 1.0);
+    }
+
+    fn reset(&mut self) {
+        self.phase = DEFAULT_PHASE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.quality = quality;
+    }
+}
+
+// Casio CZ phase distortion: the first half of the wave is squeezed into the part of the cycle
+// before the knee and the second half stretched over the rest. Also returns how fast the distorted
+// phase is moving, for the band limiting
+fn get_distorted_phase(phase: f32, phase_distortion: f32) -> (f32, f32) {
+    let knee = HALF_CYCLE - MAXIMUM_KNEE_OFFSET * phase_distortion;
+
+    if phase < knee {
+        let slope = HALF_CYCLE / knee;
+        (phase * slope, slope)
+    } else {
+        let slope = HALF_CYCLE / (1.0 - knee);
+        (HALF_CYCLE + (phase - knee) * slope, slope)
+    }
+}
@@ -1,10 +1,107 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::{GenerateSamples, did_phase_wrap, get_next_phase};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 
-pub struct Noise {}
+pub const DEFAULT_NOISE_SEED: u64 = 0x5EED;
+const DEFAULT_PHASE: f32 = 0.0;
+const PINK_NOISE_LEVEL: f32 = 0.11;
+const BROWN_NOISE_LEAK: f32 = 0.02;
+const BROWN_NOISE_LEVEL: f32 = 3.5;
+const BLUE_NOISE_LEVEL: f32 = 2.0;
+const VIOLET_NOISE_LEVEL: f32 = 0.5;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum NoiseColor {
+    #[default]
+    White,
+    Pink,
+    Brown,
+    Blue,
+    Violet,
+    SampleAndHold,
+}
+
+pub struct Noise {
+    color: NoiseColor,
+    seed: u64,
+    random_number_generator: SmallRng,
+    pink_filter_state: [f32; 7],
+    brown_level: f32,
+    previous_pink_sample: f32,
+    previous_white_sample: f32,
+    held_sample: f32,
+    phase: f32,
+    sample_rate: f32,
+}
 
 impl Noise {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(seed: u64, sample_rate: f32) -> Self {
+        Self {
+            color: NoiseColor::default(),
+            seed,
+            random_number_generator: SmallRng::seed_from_u64(seed),
+            pink_filter_state: [0.0; 7],
+            brown_level: 0.0,
+            previous_pink_sample: 0.0,
+            previous_white_sample: 0.0,
+            held_sample: 0.0,
+            phase: DEFAULT_PHASE,
+            sample_rate,
+        }
+    }
+
+    fn next_white_sample(&mut self) -> f32 {
+        self.random_number_generator.random_range(-1.0..=1.0)
+    }
+
+    // Paul Kellet's refined pink filter, within 0.05 dB of -3 dB per octave across the audio band
+    fn next_pink_sample(&mut self) -> f32 {
+        let white = self.next_white_sample();
+        let state = &mut self.pink_filter_state;
+
+        state[0] = 0.99886 * state[0] + white * 0.0555179;
+        state[1] = 0.99332 * state[1] + white * 0.0750759;
+        state[2] = 0.969 * state[2] + white * 0.153_852;
+        state[3] = 0.8665 * state[3] + white * 0.3104856;
+        state[4] = 0.55 * state[4] + white * 0.5329522;
+        state[5] = -0.7616 * state[5] - white * 0.0168980;
+        let pink = state[0..6].iter().sum::<f32>() + state[6] + white * 0.5362;
+        state[6] = white * 0.115926;
+
+        pink * PINK_NOISE_LEVEL
+    }
+
+    // Integrated white noise, leaking back to zero so it can't wander off
+    fn next_brown_sample(&mut self) -> f32 {
+        let white = self.next_white_sample();
+        self.brown_level = (self.brown_level + BROWN_NOISE_LEAK * white) / (1.0 + BROWN_NOISE_LEAK);
+        self.brown_level * BROWN_NOISE_LEVEL
+    }
+
+    // Differentiating tilts the spectrum up by 6 dB per octave: pink becomes blue, white violet
+    fn next_blue_sample(&mut self) -> f32 {
+        let pink = self.next_pink_sample();
+        let blue = pink - self.previous_pink_sample;
+        self.previous_pink_sample = pink;
+        blue * BLUE_NOISE_LEVEL
+    }
+
+    fn next_violet_sample(&mut self) -> f32 {
+        let white = self.next_white_sample();
+        let violet = white - self.previous_white_sample;
+        self.previous_white_sample = white;
+        violet * VIOLET_NOISE_LEVEL
+    }
+
+    // Picks a new value once per cycle of the note, so it plays in tune like an old console
+    fn next_sample_and_hold_sample(&mut self, tone_frequency: f32) -> f32 {
+        let next_phase = get_next_phase(self.phase, tone_frequency / self.sample_rate);
+        if did_phase_wrap(self.phase, next_phase) {
+            self.held_sample = self.next_white_sample();
+        }
+
+        self.phase = next_phase;
+        self.held_sample
     }
 }
 
@@ -13,10 +110,39 @@ impl GenerateSamples for Noise {
         if tone_frequency == 0.0 {
             return 0.0;
         }
-        rand::random_range(-1.0..=1.0) * modulation.unwrap_or(1.0)
+
+        match self.color {
+            NoiseColor::White => self.next_white_sample() * modulation.unwrap_or(1.0),
+            NoiseColor::Pink => self.next_pink_sample() * modulation.unwrap_or(1.0),
+            NoiseColor::Brown => self.next_brown_sample() * modulation.unwrap_or(1.0),
+            NoiseColor::Blue => self.next_blue_sample() * modulation.unwrap_or(1.0),
+            NoiseColor::Violet => self.next_violet_sample() * modulation.unwrap_or(1.0),
+            NoiseColor::SampleAndHold => {
+                self.next_sample_and_hold_sample(tone_frequency * modulation.unwrap_or(1.0))
+            }
+        }
     }
 
-    fn set_shape_specific_parameters(&mut self, _parameter: (f32, f32)) {}
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.color = get_noise_color_from_index(parameters.0.round() as i32);
+    }
 
-    fn reset(&mut self) {}
+    // Starts the sequence over, so a resync repeats the same noise
+    fn reset(&mut self) {
+        let color = self.color;
+        *self = Self::new(self.seed, self.sample_rate);
+        self.color = color;
+    }
+}
+
+fn get_noise_color_from_index(index: i32) -> NoiseColor {
+    match index {
+        0 => NoiseColor::White,
+        1 => NoiseColor::Pink,
+        2 => NoiseColor::Brown,
+        3 => NoiseColor::Blue,
+        4 => NoiseColor::Violet,
+        5 => NoiseColor::SampleAndHold,
+        _ => Default::default(),
+    }
 }
@@ -0,0 +1,133 @@
+use crate::synth::oscillators::GenerateSamples;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+const LOWEST_FREQUENCY: f32 = 20.0;
+const DEFAULT_DAMPING: f32 = 0.5;
+const DEFAULT_DECAY: f32 = 0.5;
+const MAXIMUM_LOOP_FILTER_DELAY: f32 = 0.5;
+const MINIMUM_DECAY_SECONDS: f32 = 0.1;
+const MAXIMUM_DECAY_SECONDS: f32 = 10.0;
+const DECIBELS_TO_SILENCE: f32 = 60.0;
+
+// Keeping the allpass delay between 0.1 and 1.1 samples keeps its coefficient away from -1,
+// where it rings and its delay goes wild
+const MINIMUM_ALLPASS_DELAY: f32 = 0.1;
+
+// Karplus-Strong string: a burst of noise circulating through a tuned delay line, losing a little
+// of its level and its highs on every trip around the loop
+pub struct Pluck {
+    delay_line: Vec<f32>,
+    write_index: usize,
+    loop_filter_delay: f32,
+    decay_seconds: f32,
+    previous_delayed_sample: f32,
+    allpass_previous_input: f32,
+    allpass_previous_output: f32,
+    is_excitation_pending: bool,
+    random_number_generator: SmallRng,
+    sample_rate: f32,
+}
+
+impl Pluck {
+    pub fn new(seed: u64, sample_rate: f32) -> Self {
+        let delay_line_length = (sample_rate / LOWEST_FREQUENCY).ceil() as usize + 2;
+
+        let mut pluck = Self {
+            delay_line: vec![0.0; delay_line_length],
+            write_index: 0,
+            loop_filter_delay: 0.0,
+            decay_seconds: 0.0,
+            previous_delayed_sample: 0.0,
+            allpass_previous_input: 0.0,
+            allpass_previous_output: 0.0,
+            is_excitation_pending: true,
+            random_number_generator: SmallRng::seed_from_u64(seed),
+            sample_rate,
+        };
+
+        pluck.set_shape_specific_parameters((DEFAULT_DAMPING, DEFAULT_DECAY));
+        pluck
+    }
+
+    // Fills the string with one period of noise, with any DC removed so it doesn't hang around as
+    // an offset after the tone has died away
+    fn excite(&mut self, period: usize) {
+        let length = self.delay_line.len();
+        let start = self.write_index + length - period;
+
+        let mut sum = 0.0;
+        for offset in 0..period {
+            let sample = self.random_number_generator.random_range(-1.0..=1.0);
+            self.delay_line[(start + offset) % length] = sample;
+            sum += sample;
+        }
+
+        let average = sum / period as f32;
+        for offset in 0..period {
+            self.delay_line[(start + offset) % length] -= average;
+        }
+
+        self.previous_delayed_sample = 0.0;
+        self.allpass_previous_input = 0.0;
+        self.allpass_previous_output = 0.0;
+        self.is_excitation_pending = false;
+    }
+}
+
+impl GenerateSamples for Pluck {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let new_frequency = (tone_frequency * modulation.unwrap_or(1.0)).abs();
+        if new_frequency == 0.0 {
+            return 0.0;
+        }
+
+        let length = self.delay_line.len();
+
+        // The loop filter and the allpass both add delay, so the delay line is shortened to match
+        let loop_delay = (self.sample_rate / new_frequency).clamp(2.0, (length - 1) as f32);
+        let delay_line_delay = loop_delay - self.loop_filter_delay;
+        let delay = ((delay_line_delay - MINIMUM_ALLPASS_DELAY).floor() as usize).max(1);
+        let allpass_delay = delay_line_delay - delay as f32;
+        let allpass_coefficient = (1.0 - allpass_delay) / (1.0 + allpass_delay);
+
+        if self.is_excitation_pending {
+            self.excite(loop_delay.round() as usize);
+        }
+
+        let delayed_sample = self.delay_line[(self.write_index + length - delay) % length];
+
+        // Two point average, weighted by the damping. Its delay is that weight, in samples
+        let filtered_sample = delayed_sample * (1.0 - self.loop_filter_delay)
+            + self.previous_delayed_sample * self.loop_filter_delay;
+        self.previous_delayed_sample = delayed_sample;
+
+        let tuned_sample = allpass_coefficient * filtered_sample + self.allpass_previous_input
+            - allpass_coefficient * self.allpass_previous_output;
+        self.allpass_previous_input = filtered_sample;
+        self.allpass_previous_output = tuned_sample;
+
+        // Loses 60 dB over the decay time, however many trips around the loop that takes
+        let loop_gain =
+            10.0f32.powf(-DECIBELS_TO_SILENCE / 20.0 / (self.decay_seconds * new_frequency));
+        self.delay_line[self.write_index] = tuned_sample * loop_gain;
+        self.write_index = (self.write_index + 1) % length;
+
+        delayed_sample
+    }
+
+    // Damping from bright to dull, then decay on an exponential scale
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.loop_filter_delay = parameters.0.clamp(0.0, 1.0) * MAXIMUM_LOOP_FILTER_DELAY;
+        self.decay_seconds = MINIMUM_DECAY_SECONDS
+            * (MAXIMUM_DECAY_SECONDS / MINIMUM_DECAY_SECONDS).powf(parameters.1.clamp(0.0, 1.0));
+    }
+
+    fn reset(&mut self) {
+        self.is_excitation_pending = true;
+    }
+
+    fn start_note(&mut self) {
+        self.is_excitation_pending = true;
+    }
+}
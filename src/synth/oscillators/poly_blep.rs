@@ -0,0 +1,21 @@
+// Two sample polynomial residual that smooths a unit step at phase zero
+pub fn poly_blep(phase: f32, phase_increment: f32) -> f32 {
+    // The residual only depends on how close the phase is to the step, so running backwards, as
+    // through zero FM does, smooths it the same way
+    let phase_increment = phase_increment.abs();
+    if phase_increment == 0.0 {
+        return 0.0;
+    }
+
+    if phase < phase_increment {
+        let t = phase / phase_increment;
+        return t + t - t * t - 1.0;
+    }
+
+    if phase > 1.0 - phase_increment {
+        let t = (phase - 1.0) / phase_increment;
+        return t * t + t + t + 1.0;
+    }
+
+    0.0
+}
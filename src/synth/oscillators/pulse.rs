@@ -1,26 +1,30 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::poly_blep::poly_blep;
+use crate::synth::oscillators::{
+    GenerateSamples, OscillatorQuality, did_phase_wrap, get_next_phase,
+};
 
 const PI: f32 = std::f32::consts::PI;
-const DEFAULT_X_COORDINATE: f32 = 0.0;
-const DEFAULT_X_INCREMENT: f32 = 1.0;
+const DEFAULT_PHASE: f32 = 0.0;
 
 const DEFAULT_PULSE_WIDTH_ADJUSTMENT: f32 = 0.5;
 const OSCILLATOR_MOD_TO_PWM_ADJUSTMENT_FACTOR: f32 = 0.5;
 
 pub struct Pulse {
-    x_coordinate: f32,
+    phase: f32,
+    phase_has_wrapped: bool,
     sample_rate: f32,
     pulse_width: f32,
+    quality: OscillatorQuality,
 }
 
 impl Pulse {
     pub fn new(sample_rate: f32) -> Self {
-        let x_coordinate = DEFAULT_X_COORDINATE;
-
         Self {
-            x_coordinate,
+            phase: DEFAULT_PHASE,
+            phase_has_wrapped: false,
             sample_rate,
             pulse_width: DEFAULT_PULSE_WIDTH_ADJUSTMENT,
+            quality: OscillatorQuality::default(),
         }
     }
 }
@@ -36,16 +40,25 @@ impl GenerateSamples for Pulse {
             }
         };
 
-        let mut y_coordinate: f32 =
-            (tone_frequency * (2.0 * PI) * (self.x_coordinate / self.sample_rate)).sin();
+        // The share of the cycle a sine compared against -duty_cycle spends high
+        let high_phase_length = 0.5 + duty_cycle.clamp(-1.0, 1.0).asin() / PI;
+        let phase_increment = tone_frequency / self.sample_rate;
 
-        if y_coordinate >= 0.0 - duty_cycle {
-            y_coordinate = 1.0;
+        let mut y_coordinate = if self.phase < high_phase_length {
+            1.0
         } else {
-            y_coordinate = -1.0;
+            -1.0
+        };
+
+        if self.quality == OscillatorQuality::BandLimited {
+            let falling_edge_phase = (self.phase - high_phase_length).rem_euclid(1.0);
+            y_coordinate += poly_blep(self.phase, phase_increment);
+            y_coordinate -= poly_blep(falling_edge_phase, phase_increment);
         }
 
-        self.x_coordinate += DEFAULT_X_INCREMENT;
+        let next_phase = get_next_phase(self.phase, phase_increment);
+        self.phase_has_wrapped = did_phase_wrap(self.phase, next_phase);
+        self.phase = next_phase;
         y_coordinate
     }
 
@@ -54,6 +67,18 @@ impl GenerateSamples for Pulse {
     }
 
     fn reset(&mut self) {
-        self.x_coordinate = DEFAULT_X_COORDINATE;
+        self.phase = DEFAULT_PHASE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.quality = quality;
     }
 }
@@ -1,21 +1,24 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::poly_blep::poly_blep;
+use crate::synth::oscillators::{
+    GenerateSamples, OscillatorQuality, did_phase_wrap, get_next_phase,
+};
 
-const PI: f32 = std::f32::consts::PI;
-const DEFAULT_X_COORDINATE: f32 = 0.0;
-const DEFAULT_X_INCREMENT: f32 = 1.0;
+const DEFAULT_PHASE: f32 = 0.0;
 
 pub struct Ramp {
-    x_coordinate: f32,
+    phase: f32,
+    phase_has_wrapped: bool,
     sample_rate: f32,
+    quality: OscillatorQuality,
 }
 
 impl Ramp {
     pub fn new(sample_rate: f32) -> Self {
-        let x_coordinate = DEFAULT_X_COORDINATE;
-
         Self {
-            x_coordinate,
+            phase: DEFAULT_PHASE,
+            phase_has_wrapped: false,
             sample_rate,
+            quality: OscillatorQuality::default(),
         }
     }
 }
@@ -23,16 +26,33 @@ impl Ramp {
 impl GenerateSamples for Ramp {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
         let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let phase_increment = new_frequency / self.sample_rate;
 
-        let y_coordinate: f32 = (2.0 / PI)
-            * (1.0f32 / (new_frequency * PI * (self.x_coordinate / self.sample_rate)).tan()).atan();
+        let mut y_coordinate = 1.0 - 2.0 * self.phase;
+        if self.quality == OscillatorQuality::BandLimited {
+            y_coordinate += poly_blep(self.phase, phase_increment);
+        }
 
-        self.x_coordinate += DEFAULT_X_INCREMENT;
+        let next_phase = get_next_phase(self.phase, phase_increment);
+        self.phase_has_wrapped = did_phase_wrap(self.phase, next_phase);
+        self.phase = next_phase;
         y_coordinate
     }
 
     fn set_shape_specific_parameters(&mut self, _parameter: (f32, f32)) {}
     fn reset(&mut self) {
-        self.x_coordinate = DEFAULT_X_COORDINATE;
+        self.phase = DEFAULT_PHASE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.quality = quality;
     }
 }
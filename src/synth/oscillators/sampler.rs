@@ -0,0 +1,226 @@
+use crate::synth::oscillators::{GenerateSamples, ShapeData, ShapeParameter};
+use crate::wav;
+use std::error::Error;
+use std::mem;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+// Middle C, where a sample plays back at its recorded pitch until a root key is chosen
+const DEFAULT_ROOT_FREQUENCY: f32 = 261.625;
+const DEFAULT_LOOP_START: f32 = 0.0;
+const DEFAULT_LOOP_END: f32 = 1.0;
+const MINIMUM_LOOP_LENGTH: usize = 2;
+
+static EMPTY_SAMPLE: OnceLock<Arc<SampleData>> = OnceLock::new();
+
+#[derive(Default)]
+pub struct SampleData {
+    samples: Vec<f32>,
+    sample_rate: f32,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum LoopMode {
+    #[default]
+    Loop,
+    OneShot,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum SampleInterpolation {
+    Linear,
+    #[default]
+    Cubic,
+}
+
+pub struct Sampler {
+    sample: Arc<SampleData>,
+    position: f64,
+    is_playing: bool,
+    root_frequency: f32,
+    loop_start: f32,
+    loop_end: f32,
+    loop_mode: LoopMode,
+    interpolation: SampleInterpolation,
+    sample_rate: f32,
+}
+
+impl Sampler {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample: get_empty_sample(),
+            position: 0.0,
+            is_playing: true,
+            root_frequency: DEFAULT_ROOT_FREQUENCY,
+            loop_start: DEFAULT_LOOP_START,
+            loop_end: DEFAULT_LOOP_END,
+            loop_mode: LoopMode::default(),
+            interpolation: SampleInterpolation::default(),
+            sample_rate,
+        }
+    }
+
+    // The loop points are kept as fractions of the sample, so they carry over to a newly loaded one
+    fn get_loop_points(&self) -> Option<(usize, usize)> {
+        let length = self.sample.samples.len();
+        if self.loop_mode == LoopMode::OneShot || length < MINIMUM_LOOP_LENGTH {
+            return None;
+        }
+
+        let loop_start =
+            ((self.loop_start * length as f32) as usize).min(length - MINIMUM_LOOP_LENGTH);
+        let loop_end = ((self.loop_end * length as f32) as usize)
+            .clamp(loop_start + MINIMUM_LOOP_LENGTH, length);
+
+        Some((loop_start, loop_end))
+    }
+
+    // Reads past the loop end come from the start of the loop, and reads past the end of a one
+    // shot are silent
+    fn get_sample_at(&self, index: isize, loop_points: Option<(usize, usize)>) -> f32 {
+        let samples = &self.sample.samples;
+        let index = match loop_points {
+            Some((loop_start, loop_end)) if index >= loop_end as isize => {
+                let loop_length = (loop_end - loop_start) as isize;
+                loop_start as isize + (index - loop_start as isize) % loop_length
+            }
+            _ => index,
+        };
+
+        samples.get(index.max(0) as usize).copied().unwrap_or(0.0)
+    }
+}
+
+impl GenerateSamples for Sampler {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        if !self.is_playing || self.sample.samples.is_empty() {
+            return 0.0;
+        }
+
+        let loop_points = self.get_loop_points();
+        let index = self.position.floor() as isize;
+        let fraction = (self.position - index as f64) as f32;
+
+        let sample = match self.interpolation {
+            SampleInterpolation::Linear => {
+                let current_sample = self.get_sample_at(index, loop_points);
+                let next_sample = self.get_sample_at(index + 1, loop_points);
+                current_sample + (next_sample - current_sample) * fraction
+            }
+            SampleInterpolation::Cubic => get_hermite_interpolated_sample(
+                [
+                    self.get_sample_at(index - 1, loop_points),
+                    self.get_sample_at(index, loop_points),
+                    self.get_sample_at(index + 1, loop_points),
+                    self.get_sample_at(index + 2, loop_points),
+                ],
+                fraction,
+            ),
+        };
+
+        // Played at the root key, the sample comes out at the speed it was recorded
+        let new_frequency = (tone_frequency * modulation.unwrap_or(1.0)).abs();
+        let playback_rate =
+            new_frequency / self.root_frequency * self.sample.sample_rate / self.sample_rate;
+        self.position += playback_rate as f64;
+
+        match loop_points {
+            Some((loop_start, loop_end)) if self.position >= loop_end as f64 => {
+                let loop_length = (loop_end - loop_start) as f64;
+                self.position =
+                    loop_start as f64 + (self.position - loop_start as f64) % loop_length;
+            }
+            None if self.position >= self.sample.samples.len() as f64 => {
+                self.is_playing = false;
+            }
+            _ => {}
+        }
+
+        sample
+    }
+
+    // Loop start and end, as fractions of the sample
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.loop_start = parameters.0.clamp(0.0, 1.0);
+        self.loop_end = parameters.1.clamp(0.0, 1.0);
+    }
+
+    fn reset(&mut self) {
+        self.position = 0.0;
+        self.is_playing = true;
+    }
+
+    fn start_note(&mut self) {
+        self.reset();
+    }
+
+    fn set_shape_parameter(&mut self, parameter: ShapeParameter) {
+        match parameter {
+            ShapeParameter::SamplerRootFrequency(root_frequency) if root_frequency > 0.0 => {
+                self.root_frequency = root_frequency;
+            }
+            ShapeParameter::SamplerLoopMode(loop_mode) => self.loop_mode = loop_mode,
+            ShapeParameter::SamplerInterpolation(interpolation) => {
+                self.interpolation = interpolation;
+            }
+            _ => {}
+        }
+    }
+
+    fn swap_shape_data(&mut self, shape_data: &mut ShapeData) {
+        if let ShapeData::Sample(sample) = shape_data {
+            mem::swap(&mut self.sample, sample);
+            self.reset();
+        }
+    }
+}
+
+pub fn load_sample_from_file(path: &Path) -> Result<SampleData, Box<dyn Error>> {
+    let (samples, sample_rate) = wav::read_first_channel_from_file(path)?;
+
+    Ok(SampleData {
+        samples,
+        sample_rate: sample_rate as f32,
+    })
+}
+
+pub fn get_loop_mode_from_mode_name(mode_name: &str) -> LoopMode {
+    match mode_name {
+        "Loop" => LoopMode::Loop,
+        "One Shot" => LoopMode::OneShot,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_sample_interpolation_from_interpolation_name(
+    interpolation_name: &str,
+) -> SampleInterpolation {
+    match interpolation_name {
+        "Linear" => SampleInterpolation::Linear,
+        "Cubic" => SampleInterpolation::Cubic,
+        _ => Default::default(),
+    }
+}
+
+fn get_empty_sample() -> Arc<SampleData> {
+    EMPTY_SAMPLE
+        .get_or_init(|| Arc::new(SampleData::default()))
+        .clone()
+}
+
+// Four point, third order Hermite. It passes through both middle points with the slopes of its
+// neighbours, so the curve stays smooth from one sample to the next
+fn get_hermite_interpolated_sample(samples: [f32; 4], fraction: f32) -> f32 {
+    let [
+        previous_sample,
+        current_sample,
+        next_sample,
+        after_next_sample,
+    ] = samples;
+
+    let c1 = 0.5 * (next_sample - previous_sample);
+    let c2 = previous_sample - 2.5 * current_sample + 2.0 * next_sample - 0.5 * after_next_sample;
+    let c3 = 0.5 * (after_next_sample - previous_sample) + 1.5 * (current_sample - next_sample);
+
+    ((c3 * fraction + c2) * fraction + c1) * fraction + current_sample
+}
@@ -1,4 +1,4 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::{GenerateSamples, did_phase_wrap};
 
 const RADS_PER_CYCLE: f32 = 2.0 * std::f32::consts::PI;
 const DEFAULT_PHASE: f32 = 0.0;
@@ -6,6 +6,7 @@ const DEFAULT_PHASE: f32 = 0.0;
 pub struct Sine {
     pub phase: f32,
     pub phase_increment: f32,
+    phase_has_wrapped: bool,
 }
 
 impl Sine {
@@ -17,6 +18,7 @@ impl Sine {
         Self {
             phase,
             phase_increment,
+            phase_has_wrapped: false,
         }
     }
 }
@@ -24,12 +26,11 @@ impl Sine {
 impl GenerateSamples for Sine {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
         let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
-        self.phase += self.phase_increment * new_frequency;
-
-        if self.phase >= RADS_PER_CYCLE {
-            self.phase = 0.0;
-        }
-
+        let next_phase =
+            (self.phase + self.phase_increment * new_frequency).rem_euclid(RADS_PER_CYCLE);
+        self.phase_has_wrapped =
+            did_phase_wrap(self.phase / RADS_PER_CYCLE, next_phase / RADS_PER_CYCLE);
+        self.phase = next_phase;
         self.phase.sin()
     }
 
@@ -38,4 +39,12 @@ impl GenerateSamples for Sine {
     fn reset(&mut self) {
         self.phase = DEFAULT_PHASE;
     }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0) * RADS_PER_CYCLE;
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
 }
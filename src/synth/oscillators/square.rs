@@ -1,21 +1,25 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::poly_blep::poly_blep;
+use crate::synth::oscillators::{
+    GenerateSamples, OscillatorQuality, did_phase_wrap, get_next_phase,
+};
 
-const PI: f32 = std::f32::consts::PI;
-const DEFAULT_X_COORDINATE: f32 = 0.0;
-const DEFAULT_X_INCREMENT: f32 = 1.0;
+const DEFAULT_PHASE: f32 = 0.0;
+const HALF_CYCLE: f32 = 0.5;
 
 pub struct Square {
-    x_coordinate: f32,
+    phase: f32,
+    phase_has_wrapped: bool,
     sample_rate: f32,
+    quality: OscillatorQuality,
 }
 
 impl Square {
     pub fn new(sample_rate: f32) -> Self {
-        let x_coordinate = DEFAULT_X_COORDINATE;
-
         Self {
-            x_coordinate,
+            phase: DEFAULT_PHASE,
+            phase_has_wrapped: false,
             sample_rate,
+            quality: OscillatorQuality::default(),
         }
     }
 }
@@ -23,23 +27,37 @@ impl Square {
 impl GenerateSamples for Square {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
         let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let phase_increment = new_frequency / self.sample_rate;
 
-        let mut y_coordinate: f32 =
-            (new_frequency * (2.0 * PI) * (self.x_coordinate / self.sample_rate)).sin();
+        let mut y_coordinate = if self.phase < HALF_CYCLE { 1.0 } else { -1.0 };
 
-        if y_coordinate >= 0.0 {
-            y_coordinate = 1.0;
-        } else {
-            y_coordinate = -1.0;
+        if self.quality == OscillatorQuality::BandLimited {
+            let falling_edge_phase = (self.phase + HALF_CYCLE).rem_euclid(1.0);
+            y_coordinate += poly_blep(self.phase, phase_increment);
+            y_coordinate -= poly_blep(falling_edge_phase, phase_increment);
         }
 
-        self.x_coordinate += DEFAULT_X_INCREMENT;
+        let next_phase = get_next_phase(self.phase, phase_increment);
+        self.phase_has_wrapped = did_phase_wrap(self.phase, next_phase);
+        self.phase = next_phase;
         y_coordinate
     }
 
     fn set_shape_specific_parameters(&mut self, _parameter: (f32, f32)) {}
 
     fn reset(&mut self) {
-        self.x_coordinate = DEFAULT_X_COORDINATE;
+        self.phase = DEFAULT_PHASE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.quality = quality;
     }
 }
@@ -1,23 +1,36 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::{GenerateSamples, OscillatorQuality, ShapeData, ShapeParameter};
 
 const LOWEST_FREQUENCY_TO_SUB: f32 = 33.0;
+const DEFAULT_OCTAVES_DOWN: u32 = 1;
+const MAXIMUM_OCTAVES_DOWN: u32 = 2;
 
 pub struct Sub {
     oscillator: Box<dyn GenerateSamples + Send + Sync>,
+    octaves_down: u32,
 }
 
 impl Sub {
     pub fn new(oscillator: Box<dyn GenerateSamples + Send + Sync>) -> Self {
-        Self { oscillator }
+        Self {
+            oscillator,
+            octaves_down: DEFAULT_OCTAVES_DOWN,
+        }
     }
 }
 
 impl GenerateSamples for Sub {
-    fn next_sample(&mut self, mut tone_frequency: f32, modulation: Option<f32>) -> f32 {
-        if tone_frequency > LOWEST_FREQUENCY_TO_SUB {
-            tone_frequency *= 0.5;
-        }
-        self.oscillator.next_sample(tone_frequency, modulation)
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        self.oscillator.next_sample(
+            get_sub_frequency(tone_frequency, self.octaves_down),
+            modulation,
+        )
+    }
+
+    fn next_stereo_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> (f32, f32) {
+        self.oscillator.next_stereo_sample(
+            get_sub_frequency(tone_frequency, self.octaves_down),
+            modulation,
+        )
     }
 
     fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
@@ -27,4 +40,56 @@ impl GenerateSamples for Sub {
     fn reset(&mut self) {
         self.oscillator.reset();
     }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.oscillator.has_phase_wrapped()
+    }
+
+    fn hard_sync(&mut self) {
+        self.oscillator.hard_sync();
+    }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.oscillator.set_quality(quality);
+    }
+
+    fn start_note(&mut self) {
+        self.oscillator.start_note();
+    }
+
+    fn release_note(&mut self) {
+        self.oscillator.release_note();
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.oscillator.set_phase(phase);
+    }
+
+    // The octave comes in as an offset, so -1 is one octave down
+    fn set_shape_parameter(&mut self, parameter: ShapeParameter) {
+        match parameter {
+            ShapeParameter::SubOctave(octave) => {
+                self.octaves_down = octave
+                    .unsigned_abs()
+                    .clamp(DEFAULT_OCTAVES_DOWN, MAXIMUM_OCTAVES_DOWN);
+            }
+            parameter => self.oscillator.set_shape_parameter(parameter),
+        }
+    }
+
+    fn swap_shape_data(&mut self, shape_data: &mut ShapeData) {
+        self.oscillator.swap_shape_data(shape_data);
+    }
+}
+
+// Each octave down is only taken while the note stays above the lowest frequency to sub
+fn get_sub_frequency(tone_frequency: f32, octaves_down: u32) -> f32 {
+    let mut sub_frequency = tone_frequency;
+    for _ in 0..octaves_down {
+        if sub_frequency.abs() > LOWEST_FREQUENCY_TO_SUB {
+            sub_frequency *= 0.5;
+        }
+    }
+
+    sub_frequency
 }
@@ -1,65 +1,249 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::poly_blep::poly_blep;
+use crate::synth::oscillators::{
+    GenerateSamples, OscillatorQuality, ShapeParameter, did_phase_wrap, get_next_phase,
+};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
 
-const PI: f32 = std::f32::consts::PI;
-const DEFAULT_X_COORDINATE: f32 = 0.0;
-const DEFAULT_X_INCREMENT: f32 = 1.0;
-const VOICE_FREQUENCY_SPREAD: [f32; 7] = [0.97, 0.98, 0.99, 1.0, 1.01, 1.02, 1.03];
+const MINIMUM_NUMBER_OF_VOICES: usize = 1;
+const MAXIMUM_NUMBER_OF_VOICES: usize = 9;
+const DEFAULT_NUMBER_OF_VOICES: usize = 7;
+const DEFAULT_DETUNE_AMOUNT: f32 = 0.5;
+const DEFAULT_MIX: f32 = 0.5;
+const DEFAULT_STEREO_SPREAD: f32 = 0.8;
+
+// How far the outermost side voices of a JP-8000 sit from the center voice at full detune,
+// averaged over both sides. Voice counts other than seven interpolate between these
+const JP8000_SIDE_VOICE_OFFSETS: [f32; 3] = [0.019_717_885, 0.062_524_885, 0.108_737_775];
+
+// Polynomial fit of the JP-8000 detune knob, lowest order first. It barely moves for most of its
+// travel and shoots up over the last quarter
+const JP8000_DETUNE_CURVE: [f64; 12] = [
+    0.003_011_559_6,
+    0.671_741_763_4,
+    -24.187_882_439_1,
+    404.270_393_838_8,
+    -3_425.083_659_131_8,
+    17_019.951_858_008,
+    -53_046.964_275_187_5,
+    106_649.667_915_829_2,
+    -138_150.676_108_054_8,
+    111_363.480_872_936_8,
+    -50_818.865_204_592_4,
+    10_028.731_289_163_4,
+];
 
 pub struct SuperSaw {
-    x_coordinate: f32,
-    x_increment: f32,
+    voice_phases: [f32; MAXIMUM_NUMBER_OF_VOICES],
+    voice_frequency_ratios: [f32; MAXIMUM_NUMBER_OF_VOICES],
+    voice_pan_levels: [(f32, f32); MAXIMUM_NUMBER_OF_VOICES],
+    number_of_voices: usize,
+    detune_amount: f32,
+    stereo_spread: f32,
+    center_level: f32,
+    side_level: f32,
+    center_phase_has_wrapped: bool,
+    random_number_generator: SmallRng,
     sample_rate: f32,
+    quality: OscillatorQuality,
 }
 
 impl SuperSaw {
-    pub fn new(sample_rate: f32) -> Self {
-        let x_coordinate = DEFAULT_X_COORDINATE;
-        let x_increment = DEFAULT_X_INCREMENT;
-
-        Self {
-            x_coordinate,
-            x_increment,
+    pub fn new(seed: u64, sample_rate: f32) -> Self {
+        let mut super_saw = Self {
+            voice_phases: [0.0; MAXIMUM_NUMBER_OF_VOICES],
+            voice_frequency_ratios: [1.0; MAXIMUM_NUMBER_OF_VOICES],
+            voice_pan_levels: [(1.0, 1.0); MAXIMUM_NUMBER_OF_VOICES],
+            number_of_voices: DEFAULT_NUMBER_OF_VOICES,
+            detune_amount: DEFAULT_DETUNE_AMOUNT,
+            stereo_spread: DEFAULT_STEREO_SPREAD,
+            center_level: 0.0,
+            side_level: 0.0,
+            center_phase_has_wrapped: false,
+            random_number_generator: SmallRng::seed_from_u64(seed),
             sample_rate,
+            quality: OscillatorQuality::default(),
+        };
+
+        super_saw.randomize_voice_phases();
+        super_saw.update_voice_spread();
+        super_saw.set_mix(DEFAULT_MIX);
+        super_saw
+    }
+
+    // Free running analog voices never line up, so neither should ours
+    fn randomize_voice_phases(&mut self) {
+        let random_number_generator = &mut self.random_number_generator;
+        self.voice_phases
+            .iter_mut()
+            .for_each(|phase| *phase = random_number_generator.random());
+    }
+
+    // Voice 0 is the center voice. The side voices pair up on either side of it, each pair
+    // detuned further and panned wider than the one before
+    fn update_voice_spread(&mut self) {
+        let detune = get_jp8000_detune(self.detune_amount);
+        let number_of_side_voices = self.number_of_voices - 1;
+        let number_of_pairs = number_of_side_voices.div_ceil(2).max(1);
+
+        self.voice_frequency_ratios[0] = 1.0;
+        self.voice_pan_levels[0] = get_pan_levels(0.0);
+
+        for side_voice in 0..number_of_side_voices {
+            let distance = (side_voice / 2 + 1) as f32 / number_of_pairs as f32;
+            let side = if side_voice.is_multiple_of(2) {
+                1.0
+            } else {
+                -1.0
+            };
+
+            self.voice_frequency_ratios[side_voice + 1] =
+                1.0 + side * detune * get_side_voice_offset(distance);
+            self.voice_pan_levels[side_voice + 1] =
+                get_pan_levels(side * distance * self.stereo_spread);
         }
     }
+
+    // The JP-8000 mix curve: the center voice fades a little as the side voices come up a lot
+    fn set_mix(&mut self, mix: f32) {
+        let mix = mix.clamp(0.0, 1.0);
+        self.center_level = -0.553_66 * mix + 0.997_85;
+        self.side_level = -0.737_64 * mix * mix + 1.284_1 * mix + 0.044_372;
+    }
+
+    fn next_voice_samples(
+        &mut self,
+        tone_frequency: f32,
+        modulation: Option<f32>,
+    ) -> [f32; MAXIMUM_NUMBER_OF_VOICES] {
+        let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
+        let side_level = if self.number_of_voices > 1 {
+            self.side_level
+        } else {
+            0.0
+        };
+        let level_sum = self.center_level + side_level * (self.number_of_voices - 1) as f32;
+        let mut voice_samples = [0.0; MAXIMUM_NUMBER_OF_VOICES];
+
+        let voices = voice_samples
+            .iter_mut()
+            .zip(self.voice_phases.iter_mut())
+            .zip(self.voice_frequency_ratios)
+            .take(self.number_of_voices)
+            .enumerate();
+
+        for (voice, ((voice_sample, phase), frequency_ratio)) in voices {
+            let phase_increment = new_frequency * frequency_ratio / self.sample_rate;
+
+            let mut sample = 2.0 * *phase - 1.0;
+            if self.quality == OscillatorQuality::BandLimited {
+                sample -= poly_blep(*phase, phase_increment);
+            }
+
+            let level = if voice == 0 {
+                self.center_level
+            } else {
+                side_level
+            };
+            *voice_sample = sample * level / level_sum;
+
+            let next_phase = get_next_phase(*phase, phase_increment);
+            if voice == 0 {
+                self.center_phase_has_wrapped = did_phase_wrap(*phase, next_phase);
+            }
+            *phase = next_phase;
+        }
+
+        voice_samples
+    }
 }
 
 impl GenerateSamples for SuperSaw {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
-        let mut voice_samples: Vec<f32> = vec![];
-
-        for frequency_offset in VOICE_FREQUENCY_SPREAD {
-            voice_samples.push(self.single_saw_sample(
-                tone_frequency * frequency_offset,
-                self.x_coordinate,
-                modulation,
-            ));
-        }
-
-        self.x_coordinate += self.x_increment;
+        self.next_voice_samples(tone_frequency, modulation)
+            .iter()
+            .sum()
+    }
 
-        voice_samples.iter().sum::<f32>() / 7.0
+    fn next_stereo_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> (f32, f32) {
+        self.next_voice_samples(tone_frequency, modulation)
+            .iter()
+            .zip(self.voice_pan_levels)
+            .fold(
+                (0.0, 0.0),
+                |(left, right), (sample, (left_level, right_level))| {
+                    (left + sample * left_level, right + sample * right_level)
+                },
+            )
     }
 
-    fn set_shape_specific_parameters(&mut self, _parameter: (f32, f32)) {}
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.detune_amount = parameters.0.clamp(0.0, 1.0);
+        self.update_voice_spread();
+        self.set_mix(parameters.1);
+    }
 
     fn reset(&mut self) {
-        self.x_coordinate = DEFAULT_X_COORDINATE;
-        self.x_increment = DEFAULT_X_INCREMENT;
+        self.randomize_voice_phases();
     }
-}
 
-impl SuperSaw {
-    fn single_saw_sample(
-        &mut self,
-        tone_frequency: f32,
-        x_coordinate: f32,
-        modulation: Option<f32>,
-    ) -> f32 {
-        let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
+    // The center voice is the one the ear follows, so it is the one that drives sync
+    fn has_phase_wrapped(&self) -> bool {
+        self.center_phase_has_wrapped
+    }
 
-        let y_coordinate: f32 = (-2.0 / PI)
-            * (1.0f32 / (new_frequency * PI * (x_coordinate / self.sample_rate)).tan()).atan();
-        y_coordinate
+    // A synced super saw restarts every voice together, as a random restart would just be noise
+    fn hard_sync(&mut self) {
+        self.voice_phases = [0.0; MAXIMUM_NUMBER_OF_VOICES];
     }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.quality = quality;
+    }
+
+    fn set_shape_parameter(&mut self, parameter: ShapeParameter) {
+        match parameter {
+            ShapeParameter::SuperSawVoices(number_of_voices) => {
+                self.number_of_voices = (number_of_voices.max(0) as usize)
+                    .clamp(MINIMUM_NUMBER_OF_VOICES, MAXIMUM_NUMBER_OF_VOICES);
+                self.update_voice_spread();
+            }
+            ShapeParameter::SuperSawStereoSpread(stereo_spread) => {
+                self.stereo_spread = stereo_spread.clamp(0.0, 1.0);
+                self.update_voice_spread();
+            }
+            _ => {}
+        }
+    }
+}
+
+fn get_jp8000_detune(detune_amount: f32) -> f32 {
+    JP8000_DETUNE_CURVE
+        .iter()
+        .rev()
+        .fold(0.0, |sum, coefficient| {
+            sum * detune_amount as f64 + coefficient
+        }) as f32
+}
+
+// Distance runs from just off center to 1.0 for the outermost pair
+fn get_side_voice_offset(distance: f32) -> f32 {
+    let position = distance * JP8000_SIDE_VOICE_OFFSETS.len() as f32;
+    let index = (position.ceil() as usize).clamp(1, JP8000_SIDE_VOICE_OFFSETS.len());
+    let lower_offset = if index == 1 {
+        0.0
+    } else {
+        JP8000_SIDE_VOICE_OFFSETS[index - 2]
+    };
+    let upper_offset = JP8000_SIDE_VOICE_OFFSETS[index - 1];
+    let fraction = position - (index - 1) as f32;
+
+    lower_offset + (upper_offset - lower_offset) * fraction
+}
+
+// Equal power, scaled so a centered voice keeps full level on both sides
+fn get_pan_levels(pan: f32) -> (f32, f32) {
+    let angle = (pan + 1.0) * FRAC_PI_4;
+    (angle.cos() * SQRT_2, angle.sin() * SQRT_2)
 }
@@ -1,20 +1,20 @@
-use crate::synth::oscillators::GenerateSamples;
+use crate::synth::oscillators::{GenerateSamples, did_phase_wrap, get_next_phase};
 
-const PI: f32 = std::f32::consts::PI;
-const DEFAULT_X_COORDINATE: f32 = 0.0;
-const DEFAULT_X_INCREMENT: f32 = 1.0;
+const DEFAULT_PHASE: f32 = 0.0;
+const QUARTER_CYCLE: f32 = 0.25;
+const THREE_QUARTER_CYCLE: f32 = 0.75;
 
 pub struct Triangle {
-    x_coordinate: f32,
+    phase: f32,
+    phase_has_wrapped: bool,
     sample_rate: f32,
 }
 
 impl Triangle {
     pub fn new(sample_rate: f32) -> Self {
-        let x_coordinate = DEFAULT_X_COORDINATE;
-
         Self {
-            x_coordinate,
+            phase: DEFAULT_PHASE,
+            phase_has_wrapped: false,
             sample_rate,
         }
     }
@@ -24,18 +24,32 @@ impl GenerateSamples for Triangle {
     fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
         let new_frequency = tone_frequency * modulation.unwrap_or(1.0);
 
-        let y_coordinate: f32 = 2.0 / PI
-            * (new_frequency * (2.0 * PI) * (self.x_coordinate / self.sample_rate))
-                .sin()
-                .asin();
-
-        self.x_coordinate += DEFAULT_X_INCREMENT;
+        // Rises from 0 to 1 over the first quarter, falls to -1 at three quarters, then returns to 0
+        let y_coordinate = if self.phase < QUARTER_CYCLE {
+            4.0 * self.phase
+        } else if self.phase < THREE_QUARTER_CYCLE {
+            2.0 - 4.0 * self.phase
+        } else {
+            4.0 * self.phase - 4.0
+        };
+
+        let next_phase = get_next_phase(self.phase, new_frequency / self.sample_rate);
+        self.phase_has_wrapped = did_phase_wrap(self.phase, next_phase);
+        self.phase = next_phase;
         y_coordinate
     }
 
     fn set_shape_specific_parameters(&mut self, _parameter: (f32, f32)) {}
 
     fn reset(&mut self) {
-        self.x_coordinate = DEFAULT_X_COORDINATE;
+        self.phase = DEFAULT_PHASE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
     }
 }
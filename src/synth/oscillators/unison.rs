@@ -0,0 +1,226 @@
+use crate::synth::oscillators::{GenerateSamples, OscillatorQuality, ShapeData, ShapeParameter};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::f32::consts::{FRAC_PI_4, SQRT_2};
+
+pub const MAXIMUM_UNISON_VOICES: usize = 8;
+const MINIMUM_UNISON_VOICES: usize = 1;
+const MAXIMUM_UNISON_DETUNE_CENTS: f32 = 100.0;
+
+// Stacks copies of one wave, spread evenly in pitch and across the stereo field. Only the voices
+// in use are built, and a new number of voices means a new set, built off the audio thread
+pub struct Unison {
+    voices: Vec<Box<dyn GenerateSamples + Send + Sync>>,
+    voice_frequency_ratios: [f32; MAXIMUM_UNISON_VOICES],
+    voice_pan_levels: [(f32, f32); MAXIMUM_UNISON_VOICES],
+    number_of_voices: usize,
+    detune_cents: f32,
+    width: f32,
+    is_phase_randomized: bool,
+    random_number_generator: SmallRng,
+    level: f32,
+}
+
+impl Unison {
+    pub fn new(voices: Vec<Box<dyn GenerateSamples + Send + Sync>>, seed: u64) -> Self {
+        let mut unison = Self {
+            number_of_voices: voices.len(),
+            voices,
+            voice_frequency_ratios: [1.0; MAXIMUM_UNISON_VOICES],
+            voice_pan_levels: [(1.0, 1.0); MAXIMUM_UNISON_VOICES],
+            detune_cents: 0.0,
+            width: 0.0,
+            is_phase_randomized: false,
+            random_number_generator: SmallRng::seed_from_u64(seed),
+            level: 1.0,
+        };
+
+        unison.update_voice_spread();
+        unison
+    }
+
+    // The voices sit evenly from the lowest to the highest detune, and from one side to the other,
+    // so a single voice stays in tune in the center
+    fn update_voice_spread(&mut self) {
+        let number_of_voices = self.number_of_voices;
+
+        let voices = self
+            .voice_frequency_ratios
+            .iter_mut()
+            .zip(self.voice_pan_levels.iter_mut())
+            .take(number_of_voices)
+            .enumerate();
+
+        for (voice, (frequency_ratio, pan_levels)) in voices {
+            let spread = if number_of_voices > 1 {
+                2.0 * voice as f32 / (number_of_voices - 1) as f32 - 1.0
+            } else {
+                0.0
+            };
+
+            *frequency_ratio = 2.0f32.powf(spread * self.detune_cents / 1200.0);
+            *pan_levels = get_pan_levels(spread * self.width);
+        }
+
+        self.update_level();
+    }
+
+    // Detuned voices with random phases drift in and out of phase, so on average they add up by
+    // power. Voices that start together, or never drift apart, add up in full and would clip
+    fn update_level(&mut self) {
+        let number_of_voices = self.number_of_voices as f32;
+
+        self.level = if self.detune_cents > 0.0 && self.is_phase_randomized {
+            1.0 / number_of_voices.sqrt()
+        } else {
+            1.0 / number_of_voices
+        };
+    }
+
+    fn randomize_voice_phases(&mut self) {
+        let random_number_generator = &mut self.random_number_generator;
+        self.voices
+            .iter_mut()
+            .for_each(|voice| voice.set_phase(random_number_generator.random()));
+    }
+}
+
+impl GenerateSamples for Unison {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let sample: f32 = self
+            .voices
+            .iter_mut()
+            .zip(self.voice_frequency_ratios)
+            .take(self.number_of_voices)
+            .map(|(voice, frequency_ratio)| {
+                voice.next_sample(tone_frequency * frequency_ratio, modulation)
+            })
+            .sum();
+
+        sample * self.level
+    }
+
+    fn next_stereo_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> (f32, f32) {
+        let (left_sample, right_sample) = self
+            .voices
+            .iter_mut()
+            .zip(self.voice_frequency_ratios)
+            .zip(self.voice_pan_levels)
+            .take(self.number_of_voices)
+            .fold(
+                (0.0, 0.0),
+                |(left, right), ((voice, frequency_ratio), (left_level, right_level))| {
+                    let (left_sample, right_sample) =
+                        voice.next_stereo_sample(tone_frequency * frequency_ratio, modulation);
+                    (
+                        left + left_sample * left_level,
+                        right + right_sample * right_level,
+                    )
+                },
+            );
+
+        (left_sample * self.level, right_sample * self.level)
+    }
+
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.voices
+            .iter_mut()
+            .for_each(|voice| voice.set_shape_specific_parameters(parameters));
+    }
+
+    fn reset(&mut self) {
+        self.voices.iter_mut().for_each(|voice| voice.reset());
+
+        if self.is_phase_randomized && self.number_of_voices > 1 {
+            self.randomize_voice_phases();
+        }
+    }
+
+    // The middle voice drives sync, as it is the one closest to the note
+    fn has_phase_wrapped(&self) -> bool {
+        self.voices
+            .get(self.number_of_voices / 2)
+            .is_some_and(|voice| voice.has_phase_wrapped())
+    }
+
+    // Synced voices restart together, or the sync would smear
+    fn hard_sync(&mut self) {
+        self.voices.iter_mut().for_each(|voice| voice.hard_sync());
+    }
+
+    fn set_quality(&mut self, quality: OscillatorQuality) {
+        self.voices
+            .iter_mut()
+            .for_each(|voice| voice.set_quality(quality));
+    }
+
+    fn start_note(&mut self) {
+        self.voices.iter_mut().for_each(|voice| voice.start_note());
+
+        if self.is_phase_randomized && self.number_of_voices > 1 {
+            self.randomize_voice_phases();
+        }
+    }
+
+    fn release_note(&mut self) {
+        self.voices
+            .iter_mut()
+            .for_each(|voice| voice.release_note());
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.voices
+            .iter_mut()
+            .for_each(|voice| voice.set_phase(phase));
+    }
+
+    // The unison settings are its own, everything else is for the voices
+    fn set_shape_parameter(&mut self, parameter: ShapeParameter) {
+        match parameter {
+            ShapeParameter::UnisonVoices(number_of_voices) => {
+                self.number_of_voices =
+                    get_number_of_unison_voices(number_of_voices).min(self.voices.len());
+                self.update_voice_spread();
+            }
+            ShapeParameter::UnisonDetune(detune_cents) => {
+                self.detune_cents = detune_cents.clamp(0.0, MAXIMUM_UNISON_DETUNE_CENTS);
+                self.update_voice_spread();
+            }
+            ShapeParameter::UnisonWidth(width) => {
+                self.width = width.clamp(0.0, 1.0);
+                self.update_voice_spread();
+            }
+            ShapeParameter::UnisonPhaseRandomized(is_phase_randomized) => {
+                self.is_phase_randomized = is_phase_randomized;
+                self.update_level();
+            }
+            parameter => self
+                .voices
+                .iter_mut()
+                .for_each(|voice| voice.set_shape_parameter(parameter)),
+        }
+    }
+
+    // Every voice shares the new table or sample. Only the first voice's old one is handed back,
+    // the others were holding the same one, so dropping their references here frees nothing
+    fn swap_shape_data(&mut self, shape_data: &mut ShapeData) {
+        let new_shape_data = shape_data.clone();
+        let mut voices = self.voices.iter_mut();
+
+        if let Some(first_voice) = voices.next() {
+            first_voice.swap_shape_data(shape_data);
+        }
+
+        voices.for_each(|voice| voice.swap_shape_data(&mut new_shape_data.clone()));
+    }
+}
+
+pub fn get_number_of_unison_voices(number_of_voices: i32) -> usize {
+    (number_of_voices.max(0) as usize).clamp(MINIMUM_UNISON_VOICES, MAXIMUM_UNISON_VOICES)
+}
+
+// Equal power, scaled so a centered voice keeps full level on both sides
+fn get_pan_levels(pan: f32) -> (f32, f32) {
+    let angle = (pan + 1.0) * FRAC_PI_4;
+    (angle.cos() * SQRT_2, angle.sin() * SQRT_2)
+}
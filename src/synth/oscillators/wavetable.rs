@@ -0,0 +1,297 @@
+use crate::synth::oscillators::{GenerateSamples, ShapeData, did_phase_wrap, get_next_phase};
+use crate::wav;
+use std::error::Error;
+use std::f32::consts::PI;
+use std::mem;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+// Frames are resampled to Serum's 2048 samples, whatever length the file stores them at
+const FRAME_LENGTH: usize = 2048;
+const SERUM_CHUNK_ID: &[u8; 4] = b"clm ";
+const SERUM_FRAME_LENGTH_MARKER: &str = "<!>";
+const MAXIMUM_NUMBER_OF_FRAMES: usize = 256;
+const MAXIMUM_HARMONIC: usize = FRAME_LENGTH / 2;
+const NUMBER_OF_MIP_LEVELS: usize = 11;
+const DEFAULT_NUMBER_OF_FRAMES: usize = 16;
+const DEFAULT_NUMBER_OF_HARMONICS: usize = 64;
+const DEFAULT_PHASE: f32 = 0.0;
+const DEFAULT_TABLE_POSITION: f32 = 0.0;
+const MODULATION_CENTER_VALUE: f32 = 1.0;
+
+static DEFAULT_WAVETABLE: OnceLock<Arc<WavetableData>> = OnceLock::new();
+
+// Every frame is stored once per octave, each level holding half the harmonics of the one before
+pub struct WavetableData {
+    number_of_frames: usize,
+    samples: Vec<f32>,
+}
+
+impl WavetableData {
+    fn from_frames(frames: &[Vec<f32>]) -> Self {
+        let mut samples = Vec::with_capacity(frames.len() * NUMBER_OF_MIP_LEVELS * FRAME_LENGTH);
+
+        for frame in frames {
+            samples.extend(get_mip_levels_for_frame(frame));
+        }
+
+        let peak = samples
+            .iter()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        if peak > 0.0 {
+            samples.iter_mut().for_each(|sample| *sample /= peak);
+        }
+
+        Self {
+            number_of_frames: frames.len(),
+            samples,
+        }
+    }
+
+    fn get_sample(&self, frame: usize, mip_level: usize, phase: f32) -> f32 {
+        let level_start = (frame * NUMBER_OF_MIP_LEVELS + mip_level) * FRAME_LENGTH;
+        let level = &self.samples[level_start..level_start + FRAME_LENGTH];
+
+        let position = phase * FRAME_LENGTH as f32;
+        let index = (position as usize).min(FRAME_LENGTH - 1);
+        let fraction = position - index as f32;
+
+        let current_sample = level[index];
+        let next_sample = level[(index + 1) % FRAME_LENGTH];
+        current_sample + (next_sample - current_sample) * fraction
+    }
+}
+
+pub struct Wavetable {
+    wavetable: Arc<WavetableData>,
+    phase: f32,
+    phase_has_wrapped: bool,
+    sample_rate: f32,
+    table_position: f32,
+}
+
+impl Wavetable {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            wavetable: get_default_wavetable(),
+            phase: DEFAULT_PHASE,
+            phase_has_wrapped: false,
+            sample_rate,
+            table_position: DEFAULT_TABLE_POSITION,
+        }
+    }
+
+    fn get_mip_level(&self, frequency: f32) -> usize {
+        let frequency = frequency.abs();
+        if frequency == 0.0 {
+            return 0;
+        }
+
+        let highest_playable_harmonic = (self.sample_rate / 2.0) / frequency;
+        let octaves_to_drop = (MAXIMUM_HARMONIC as f32 / highest_playable_harmonic)
+            .log2()
+            .ceil();
+
+        octaves_to_drop.clamp(0.0, (NUMBER_OF_MIP_LEVELS - 1) as f32) as usize
+    }
+}
+
+impl GenerateSamples for Wavetable {
+    fn next_sample(&mut self, tone_frequency: f32, modulation: Option<f32>) -> f32 {
+        let position_offset =
+            modulation.map_or(0.0, |modulation| modulation - MODULATION_CENTER_VALUE);
+        let table_position = (self.table_position + position_offset).clamp(0.0, 1.0);
+
+        let frame_position = table_position * (self.wavetable.number_of_frames - 1) as f32;
+        let frame = frame_position as usize;
+        let next_frame = (frame + 1).min(self.wavetable.number_of_frames - 1);
+        let frame_fraction = frame_position - frame as f32;
+
+        let mip_level = self.get_mip_level(tone_frequency);
+        let current_frame_sample = self.wavetable.get_sample(frame, mip_level, self.phase);
+        let next_frame_sample = self.wavetable.get_sample(next_frame, mip_level, self.phase);
+
+        let next_phase = get_next_phase(self.phase, tone_frequency / self.sample_rate);
+        self.phase_has_wrapped = did_phase_wrap(self.phase, next_phase);
+        self.phase = next_phase;
+        current_frame_sample + (next_frame_sample - current_frame_sample) * frame_fraction
+    }
+
+    fn set_shape_specific_parameters(&mut self, parameters: (f32, f32)) {
+        self.table_position = parameters.0.clamp(0.0, 1.0);
+    }
+
+    fn reset(&mut self) {
+        self.phase = DEFAULT_PHASE;
+    }
+
+    fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+
+    fn has_phase_wrapped(&self) -> bool {
+        self.phase_has_wrapped
+    }
+
+    fn swap_shape_data(&mut self, shape_data: &mut ShapeData) {
+        if let ShapeData::Wavetable(wavetable) = shape_data {
+            mem::swap(&mut self.wavetable, wavetable);
+        }
+    }
+}
+
+// Serum and the synths that follow it mark a wavetable with a `clm ` chunk giving the frame
+// length. Without one the whole file is taken as a single cycle
+pub fn load_wavetable_from_file(path: &Path) -> Result<WavetableData, Box<dyn Error>> {
+    let (samples, _) = wav::read_first_channel_from_file(path)?;
+    let frame_length = wav::read_chunk_from_file(path, SERUM_CHUNK_ID)?
+        .and_then(|chunk| get_frame_length_from_serum_chunk(&chunk));
+
+    let frames: Vec<Vec<f32>> = match frame_length {
+        Some(frame_length) if samples.len() >= frame_length => samples
+            .chunks_exact(frame_length)
+            .take(MAXIMUM_NUMBER_OF_FRAMES)
+            .map(get_single_cycle_resampled_to_frame_length)
+            .collect(),
+        _ => vec![get_single_cycle_resampled_to_frame_length(&samples)],
+    };
+
+    Ok(WavetableData::from_frames(&frames))
+}
+
+// The chunk reads like "<!>2048 01000000 wavetable (www.xferrecords.com)"
+fn get_frame_length_from_serum_chunk(chunk: &[u8]) -> Option<usize> {
+    let text = String::from_utf8_lossy(chunk);
+    let (_, after_marker) = text.split_once(SERUM_FRAME_LENGTH_MARKER)?;
+
+    after_marker
+        .split(|character: char| !character.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+        .filter(|frame_length| *frame_length > 0)
+}
+
+pub fn get_default_wavetable() -> Arc<WavetableData> {
+    DEFAULT_WAVETABLE
+        .get_or_init(|| Arc::new(create_sine_to_saw_wavetable()))
+        .clone()
+}
+
+// Fades the saw harmonics in one frame at a time, so position 0 is a sine and position 1 a saw
+fn create_sine_to_saw_wavetable() -> WavetableData {
+    let frames: Vec<Vec<f32>> = (0..DEFAULT_NUMBER_OF_FRAMES)
+        .map(|frame_index| {
+            let brightness = frame_index as f32 / (DEFAULT_NUMBER_OF_FRAMES - 1) as f32;
+
+            (0..FRAME_LENGTH)
+                .map(|sample_index| {
+                    let phase = sample_index as f32 / FRAME_LENGTH as f32;
+                    let upper_harmonics: f32 = (2..=DEFAULT_NUMBER_OF_HARMONICS)
+                        .map(|harmonic| {
+                            (2.0 * PI * harmonic as f32 * phase).sin() / harmonic as f32
+                        })
+                        .sum();
+
+                    (2.0 * PI * phase).sin() + upper_harmonics * brightness
+                })
+                .collect()
+        })
+        .collect();
+
+    WavetableData::from_frames(&frames)
+}
+
+fn get_single_cycle_resampled_to_frame_length(samples: &[f32]) -> Vec<f32> {
+    let step = samples.len() as f32 / FRAME_LENGTH as f32;
+
+    (0..FRAME_LENGTH)
+        .map(|index| {
+            let position = index as f32 * step;
+            let sample_index = position as usize;
+            let fraction = position - sample_index as f32;
+            let current_sample = samples[sample_index % samples.len()];
+            let next_sample = samples[(sample_index + 1) % samples.len()];
+            current_sample + (next_sample - current_sample) * fraction
+        })
+        .collect()
+}
+
+fn get_mip_levels_for_frame(frame: &[f32]) -> Vec<f32> {
+    let mut real: Vec<f32> = frame.to_vec();
+    let mut imaginary = vec![0.0; FRAME_LENGTH];
+    fft(&mut real, &mut imaginary, false);
+
+    let mut mip_levels = Vec::with_capacity(NUMBER_OF_MIP_LEVELS * FRAME_LENGTH);
+
+    for mip_level in 0..NUMBER_OF_MIP_LEVELS {
+        let highest_harmonic = MAXIMUM_HARMONIC >> mip_level;
+        let mut level_real = real.clone();
+        let mut level_imaginary = imaginary.clone();
+
+        // Drop DC and everything above this level's highest harmonic, on both sides of the spectrum
+        for bin in 0..FRAME_LENGTH {
+            let harmonic = bin.min(FRAME_LENGTH - bin);
+            if harmonic == 0 || harmonic > highest_harmonic {
+                level_real[bin] = 0.0;
+                level_imaginary[bin] = 0.0;
+            }
+        }
+
+        fft(&mut level_real, &mut level_imaginary, true);
+        mip_levels.extend(level_real);
+    }
+
+    mip_levels
+}
+
+// In place radix 2 FFT. The inverse transform is scaled by 1/n
+fn fft(real: &mut [f32], imaginary: &mut [f32], is_inverse: bool) {
+    let length = real.len();
+
+    let mut j = 0;
+    for i in 1..length {
+        let mut bit = length >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+
+        if i < j {
+            real.swap(i, j);
+            imaginary.swap(i, j);
+        }
+    }
+
+    let direction = if is_inverse { 1.0 } else { -1.0 };
+    let mut block_length = 2;
+
+    while block_length <= length {
+        let angle = direction * 2.0 * PI / block_length as f32;
+
+        for block_start in (0..length).step_by(block_length) {
+            for k in 0..block_length / 2 {
+                let (twiddle_imaginary, twiddle_real) = (angle * k as f32).sin_cos();
+                let even = block_start + k;
+                let odd = even + block_length / 2;
+
+                let odd_real = real[odd] * twiddle_real - imaginary[odd] * twiddle_imaginary;
+                let odd_imaginary = real[odd] * twiddle_imaginary + imaginary[odd] * twiddle_real;
+
+                real[odd] = real[even] - odd_real;
+                imaginary[odd] = imaginary[even] - odd_imaginary;
+                real[even] += odd_real;
+                imaginary[even] += odd_imaginary;
+            }
+        }
+
+        block_length <<= 1;
+    }
+
+    if is_inverse {
+        let scale = 1.0 / length as f32;
+        real.iter_mut().for_each(|sample| *sample *= scale);
+        imaginary.iter_mut().for_each(|sample| *sample *= scale);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::events::EventType;
+use crate::synth::engine::Engine;
+use crate::synth::oscillators::{GenerateSamples, ShapeData, WaveShape};
+use crate::synth::recorder::RecordingTap;
+use crossbeam_channel::Sender;
+use rtrb::{Consumer, Producer, PushError, RingBuffer};
+
+const COMMAND_QUEUE_CAPACITY: usize = 1024;
+const RETIRED_QUEUE_CAPACITY: usize = 1024;
+
+pub enum EngineCommand {
+    Event(EventType),
+    SwapOscillatorWaves(Vec<Box<dyn GenerateSamples + Send + Sync>>, WaveShape, i32),
+    SwapUnisonVoices(Vec<Box<dyn GenerateSamples + Send + Sync>>, i32, i32),
+    ReplaceFilterModWave(Box<dyn GenerateSamples + Send + Sync>, WaveShape),
+    SwapShapeData(Vec<ShapeData>, i32),
+    StartRecording(RecordingTap),
+    StopRecording,
+}
+
+// Anything that would free memory on the audio thread is sent back to be dropped on the control thread.
+// The payloads are only ever dropped, never read
+#[allow(dead_code)]
+enum RetiredObject {
+    Event(EventType),
+    Waves(Vec<Box<dyn GenerateSamples + Send + Sync>>),
+    Wave(Box<dyn GenerateSamples + Send + Sync>),
+    ShapeData(Vec<ShapeData>),
+    RecordingTap(RecordingTap),
+}
+
+pub struct EngineController {
+    command_producer: Producer<EngineCommand>,
+    retired_consumer: Consumer<RetiredObject>,
+}
+
+impl EngineController {
+    pub fn send(&mut self, command: EngineCommand) {
+        if let Err(PushError::Full(_)) = self.command_producer.push(command) {
+            eprintln!("Error sending command to the audio thread: the command queue is full");
+        }
+    }
+
+    pub fn drop_retired_objects(&mut self) {
+        while self.retired_consumer.pop().is_ok() {}
+    }
+}
+
+// Owns the engine inside the cpal callback. Dropping it with the stream hands the engine back
+pub struct AudioCallback {
+    engine: Option<Engine>,
+    command_consumer: Consumer<EngineCommand>,
+    retired_producer: Producer<RetiredObject>,
+    engine_return_sender: Sender<Engine>,
+}
+
+impl AudioCallback {
+    pub fn render(&mut self, buffer: &mut [f32], number_of_channels: usize) {
+        self.handle_pending_commands();
+
+        if let Some(engine) = self.engine.as_mut() {
+            engine.render(buffer, number_of_channels);
+        }
+    }
+
+    fn handle_pending_commands(&mut self) {
+        let Some(engine) = self.engine.as_mut() else {
+            return;
+        };
+
+        while let Ok(command) = self.command_consumer.pop() {
+            let Some(retired_object) = handle_command(engine, command) else {
+                continue;
+            };
+
+            // With the control thread this far behind, freeing here is the lesser evil
+            let _ = self.retired_producer.push(retired_object);
+        }
+    }
+}
+
+impl Drop for AudioCallback {
+    fn drop(&mut self) {
+        self.handle_pending_commands();
+
+        if let Some(engine) = self.engine.take()
+            && let Err(error) = self.engine_return_sender.send(engine)
+        {
+            eprintln!("Error returning the engine from the audio thread: {error}");
+        }
+    }
+}
+
+pub fn create_engine_queues(
+    engine: Engine,
+    engine_return_sender: Sender<Engine>,
+) -> (EngineController, AudioCallback) {
+    let (command_producer, command_consumer) = RingBuffer::new(COMMAND_QUEUE_CAPACITY);
+    let (retired_producer, retired_consumer) = RingBuffer::new(RETIRED_QUEUE_CAPACITY);
+
+    let controller = EngineController {
+        command_producer,
+        retired_consumer,
+    };
+
+    let audio_callback = AudioCallback {
+        engine: Some(engine),
+        command_consumer,
+        retired_producer,
+        engine_return_sender,
+    };
+
+    (controller, audio_callback)
+}
+
+fn handle_command(engine: &mut Engine, command: EngineCommand) -> Option<RetiredObject> {
+    match command {
+        EngineCommand::Event(event) => {
+            // Handled by reference so any string the event owns is freed on the control thread
+            engine.handle_event(&event);
+            Some(RetiredObject::Event(event))
+        }
+        EngineCommand::SwapOscillatorWaves(mut waves, wave_shape, oscillator) => {
+            engine.swap_oscillator_waves(&mut waves, wave_shape, oscillator);
+            Some(RetiredObject::Waves(waves))
+        }
+        EngineCommand::SwapUnisonVoices(mut waves, number_of_voices, oscillator) => {
+            engine.swap_unison_voices(&mut waves, number_of_voices, oscillator);
+            Some(RetiredObject::Waves(waves))
+        }
+        EngineCommand::ReplaceFilterModWave(wave, wave_shape) => {
+            let previous_wave = engine.replace_filter_mod_wave(wave, wave_shape);
+            Some(RetiredObject::Wave(previous_wave))
+        }
+        EngineCommand::SwapShapeData(mut shape_data, oscillator) => {
+            engine.swap_shape_data(&mut shape_data, oscillator);
+            Some(RetiredObject::ShapeData(shape_data))
+        }
+        EngineCommand::StartRecording(recording_tap) => engine
+            .start_recording(recording_tap)
+            .map(RetiredObject::RecordingTap),
+        EngineCommand::StopRecording => engine.stop_recording().map(RetiredObject::RecordingTap),
+    }
+}
@@ -0,0 +1,146 @@
+use crate::wav::{BitDepth, WavFileWriter};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const RECORDING_NUMBER_OF_CHANNELS: u16 = 2;
+const RECORDING_BUFFER_SECONDS: f32 = 4.0;
+const RECORDING_FILE_PREFIX: &str = "oscillator_playground_recording_";
+const RECORDING_FILE_EXTENSION: &str = "wav";
+const RECORDING_WRITER_INTERVAL: Duration = Duration::from_millis(50);
+
+// The audio thread's end of a recording. Frames that don't fit in the ring are counted, never waited on
+pub struct RecordingTap {
+    frame_producer: Producer<(f32, f32)>,
+    dropped_frames: Arc<AtomicU64>,
+}
+
+impl RecordingTap {
+    pub fn record_frame(&mut self, frame: (f32, f32)) {
+        if self.frame_producer.push(frame).is_err() {
+            self.dropped_frames.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+pub struct Recorder {
+    is_recording: bool,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            is_recording: false,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.is_recording
+    }
+
+    pub fn start(&mut self, sample_rate: u32) -> Result<RecordingTap, Box<dyn Error>> {
+        let file_path = get_recording_file_path()?;
+        let writer = WavFileWriter::create(
+            &file_path,
+            sample_rate,
+            RECORDING_NUMBER_OF_CHANNELS,
+            BitDepth::ThirtyTwoFloat,
+        )?;
+
+        let buffer_length = (sample_rate as f32 * RECORDING_BUFFER_SECONDS) as usize;
+        let (frame_producer, frame_consumer) = RingBuffer::new(buffer_length);
+        let dropped_frames = Arc::new(AtomicU64::new(0));
+        let writer_dropped_frames = dropped_frames.clone();
+
+        thread::spawn(move || {
+            match write_frames_to_file(frame_consumer, writer, &writer_dropped_frames) {
+                Err(error) => eprintln!("Error writing recording: {error}"),
+                Ok(_) => report_finished_recording(&file_path, &writer_dropped_frames),
+            }
+        });
+
+        self.is_recording = true;
+
+        Ok(RecordingTap {
+            frame_producer,
+            dropped_frames,
+        })
+    }
+
+    // The writer thread finishes the file once the engine lets go of its tap
+    pub fn stop(&mut self) {
+        self.is_recording = false;
+    }
+}
+
+// Empties the ring in blocks until the engine lets go of the tap. Whether it has is checked before
+// emptying, so the last frames pushed still make it into the file
+fn write_frames_to_file(
+    mut frame_consumer: Consumer<(f32, f32)>,
+    mut writer: WavFileWriter,
+    dropped_frames: &AtomicU64,
+) -> Result<(), Box<dyn Error>> {
+    let mut reported_dropped_frames = 0;
+
+    loop {
+        let tap_is_dropped = frame_consumer.is_abandoned();
+
+        let frames = frame_consumer.read_chunk(frame_consumer.slots())?;
+        for (left_sample, right_sample) in frames.into_iter() {
+            writer.write_sample(left_sample)?;
+            writer.write_sample(right_sample)?;
+        }
+
+        reported_dropped_frames = report_dropped_frames(dropped_frames, reported_dropped_frames);
+
+        if tap_is_dropped {
+            break;
+        }
+
+        thread::sleep(RECORDING_WRITER_INTERVAL);
+    }
+
+    writer.finalize()
+}
+
+// Reports drops as they happen, so a gap can be placed while the recording is still going
+fn report_dropped_frames(dropped_frames: &AtomicU64, reported_dropped_frames: u64) -> u64 {
+    let dropped_frames = dropped_frames.load(Ordering::Relaxed);
+    if dropped_frames > reported_dropped_frames {
+        eprintln!(
+            "Recording dropped {} frames the writer could not keep up with",
+            dropped_frames - reported_dropped_frames
+        );
+    }
+
+    dropped_frames
+}
+
+fn report_finished_recording(file_path: &Path, dropped_frames: &AtomicU64) {
+    let dropped_frames = dropped_frames.load(Ordering::Relaxed);
+    if dropped_frames > 0 {
+        eprintln!("Recording dropped {dropped_frames} frames in all");
+    }
+
+    println!("Recording saved to {}", file_path.display());
+}
+
+// Recordings started within the same second are numbered, rather than written over each other
+fn get_recording_file_path() -> Result<PathBuf, Box<dyn Error>> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let mut file_path = Path::new(&format!("{RECORDING_FILE_PREFIX}{timestamp}"))
+        .with_extension(RECORDING_FILE_EXTENSION);
+    let mut suffix = 1;
+
+    while file_path.exists() {
+        suffix += 1;
+        file_path = Path::new(&format!("{RECORDING_FILE_PREFIX}{timestamp}_{suffix}"))
+            .with_extension(RECORDING_FILE_EXTENSION);
+    }
+
+    Ok(file_path)
+}
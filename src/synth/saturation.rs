@@ -1,5 +1,4 @@
 const WAVE_SHAPER_MAX_AMOUNT: f32 = 0.99;
-const WAVE_SHAPER_AMOUNT_CORRECTION_FACTOR: f32 = 10.0;
 
 #[derive(Debug, Copy, Clone, PartialEq, Default)]
 pub enum SaturationMode {
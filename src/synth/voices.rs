@@ -0,0 +1,373 @@
+use crate::synth::MidiState;
+use crate::synth::arpeggiator::FIRST_REST_NOTE;
+use crate::synth::envelope::Envelope;
+use crate::synth::filter::{Filter, NUMBER_OF_FILTERS};
+use crate::synth::glide::Glide;
+use crate::synth::note_stack::NoteStack;
+use crate::synth::oscillators::Oscillators;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+pub const MAXIMUM_NUMBER_OF_VOICES: usize = 16;
+pub const ARPEGGIATOR_VOICE_INDEX: usize = 0;
+const MINIMUM_NUMBER_OF_VOICES: usize = 1;
+const DEFAULT_NUMBER_OF_VOICES: usize = 8;
+const MONO_VOICE_INDEX: usize = 0;
+const MAXIMUM_VELOCITY: f32 = 1.0;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum VoiceMode {
+    #[default]
+    Poly,
+    Mono,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum NotePriority {
+    #[default]
+    Last,
+    Low,
+    High,
+}
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum StealingMode {
+    #[default]
+    Oldest,
+    Quietest,
+    SameNote,
+}
+
+pub struct Voice {
+    pub oscillators: Oscillators,
+    pub envelope: Envelope,
+    pub filter_envelope: Envelope,
+    pub left_filters: [Filter; NUMBER_OF_FILTERS],
+    pub right_filters: [Filter; NUMBER_OF_FILTERS],
+    pub glide: Glide,
+    pub midi_note: u16,
+    pub midi_state: MidiState,
+    pub velocity: f32,
+    note_on_order: u64,
+}
+
+impl Voice {
+    fn new(seed: u64, sample_rate: f32, midi_note: u16) -> Self {
+        Self {
+            oscillators: Oscillators::new(seed, sample_rate),
+            envelope: Envelope::new(sample_rate as u32),
+            filter_envelope: Envelope::new(sample_rate as u32),
+            left_filters: [Filter::new(sample_rate), Filter::new(sample_rate)],
+            right_filters: [Filter::new(sample_rate), Filter::new(sample_rate)],
+            glide: Glide::new(sample_rate),
+            midi_note,
+            midi_state: MidiState::Rest,
+            velocity: MAXIMUM_VELOCITY,
+            note_on_order: 0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.midi_state != MidiState::Rest
+    }
+
+    pub fn start_note(&mut self, midi_note: u16, velocity: f32) {
+        if !self.is_active() {
+            self.envelope.restart_gate();
+        }
+
+        self.glide.stop();
+        self.midi_note = midi_note;
+        self.velocity = velocity;
+        self.midi_state = MidiState::NoteOn;
+        self.oscillators.start_note();
+    }
+
+    pub fn glide_to_note(&mut self, midi_note: u16) {
+        if self.midi_note >= FIRST_REST_NOTE || midi_note >= FIRST_REST_NOTE {
+            self.glide.stop();
+        } else {
+            let current_note = self.midi_note as f32 + self.glide.get_offset_semitones();
+            self.glide
+                .glide_between_notes(current_note, midi_note as f32);
+        }
+
+        self.midi_note = midi_note;
+    }
+
+    pub fn release_note(&mut self) {
+        if self.is_active() {
+            self.midi_state = MidiState::NoteOff;
+            self.oscillators.release_note();
+        }
+    }
+
+    fn is_holding_note(&self, midi_note: u16) -> bool {
+        self.midi_note == midi_note
+            && (self.midi_state == MidiState::NoteOn || self.midi_state == MidiState::NoteHold)
+    }
+}
+
+pub struct Voices {
+    voices: Vec<Voice>,
+    number_of_voices: usize,
+    stealing_mode: StealingMode,
+    voice_mode: VoiceMode,
+    note_priority: NotePriority,
+    legato_is_enabled: bool,
+    held_notes: NoteStack,
+    note_on_count: u64,
+    last_velocity: f32,
+}
+
+impl Voices {
+    pub fn new(seed: u64, sample_rate: f32, midi_note: u16) -> Self {
+        let mut seed_generator = SmallRng::seed_from_u64(seed);
+        let voices = (0..MAXIMUM_NUMBER_OF_VOICES)
+            .map(|_| Voice::new(seed_generator.random(), sample_rate, midi_note))
+            .collect();
+
+        Self {
+            voices,
+            number_of_voices: DEFAULT_NUMBER_OF_VOICES,
+            stealing_mode: StealingMode::default(),
+            voice_mode: VoiceMode::default(),
+            note_priority: NotePriority::default(),
+            legato_is_enabled: false,
+            held_notes: NoteStack::new(),
+            note_on_count: 0,
+            last_velocity: MAXIMUM_VELOCITY,
+        }
+    }
+
+    pub fn set_number_of_voices(&mut self, number_of_voices: i32) {
+        self.number_of_voices = (number_of_voices.max(0) as usize)
+            .clamp(MINIMUM_NUMBER_OF_VOICES, MAXIMUM_NUMBER_OF_VOICES);
+
+        self.voices[self.number_of_voices..]
+            .iter_mut()
+            .for_each(|voice| voice.release_note());
+    }
+
+    pub fn set_stealing_mode(&mut self, stealing_mode: StealingMode) {
+        self.stealing_mode = stealing_mode;
+    }
+
+    pub fn set_voice_mode(&mut self, voice_mode: VoiceMode) {
+        if self.voice_mode == voice_mode {
+            return;
+        }
+
+        self.voice_mode = voice_mode;
+        self.release_all_notes();
+    }
+
+    pub fn release_all_notes(&mut self) {
+        self.held_notes.clear();
+        self.voices
+            .iter_mut()
+            .for_each(|voice| voice.release_note());
+    }
+
+    pub fn set_note_priority(&mut self, note_priority: NotePriority) {
+        self.note_priority = note_priority;
+    }
+
+    pub fn set_legato_enabled(&mut self, is_enabled: bool) {
+        self.legato_is_enabled = is_enabled;
+    }
+
+    // The velocity runs from 0 to 1
+    pub fn note_on(&mut self, midi_note: u16, velocity: f32) {
+        self.last_velocity = velocity.clamp(0.0, MAXIMUM_VELOCITY);
+
+        match self.voice_mode {
+            VoiceMode::Poly => self.poly_note_on(midi_note),
+            VoiceMode::Mono => self.mono_note_on(midi_note),
+        }
+    }
+
+    pub fn note_off(&mut self, midi_note: u16) {
+        match self.voice_mode {
+            VoiceMode::Poly => self.poly_note_off(midi_note),
+            VoiceMode::Mono => self.mono_note_off(midi_note),
+        }
+    }
+
+    fn poly_note_on(&mut self, midi_note: u16) {
+        self.held_notes.push(midi_note);
+        let voice_index = self.get_voice_index_for_new_note(midi_note);
+        self.start_voice_note(voice_index, midi_note);
+    }
+
+    fn poly_note_off(&mut self, midi_note: u16) {
+        self.held_notes.remove(midi_note);
+
+        for voice_index in 0..self.voices.len() {
+            if !self.voices[voice_index].is_holding_note(midi_note) {
+                continue;
+            }
+
+            // A held key that lost its voice to stealing takes over the released voice
+            match self.get_most_recent_unvoiced_held_note() {
+                Some(fallback_note) => self.start_voice_note(voice_index, fallback_note),
+                None => self.voices[voice_index].release_note(),
+            }
+        }
+    }
+
+    fn start_voice_note(&mut self, voice_index: usize, midi_note: u16) {
+        self.note_on_count += 1;
+
+        let voice = &mut self.voices[voice_index];
+        voice.start_note(midi_note, self.last_velocity);
+        voice.note_on_order = self.note_on_count;
+    }
+
+    fn get_most_recent_unvoiced_held_note(&self) -> Option<u16> {
+        self.held_notes.iter_latest_first().find(|held_note| {
+            !self
+                .voices
+                .iter()
+                .any(|voice| voice.is_holding_note(*held_note))
+        })
+    }
+
+    fn mono_note_on(&mut self, midi_note: u16) {
+        let was_holding_notes = !self.held_notes.is_empty();
+        self.held_notes.push(midi_note);
+
+        let Some(priority_note) = self.get_priority_note() else {
+            return;
+        };
+
+        if !was_holding_notes || !self.voices[MONO_VOICE_INDEX].is_active() {
+            self.start_voice_note(MONO_VOICE_INDEX, priority_note);
+            return;
+        }
+
+        self.change_mono_note(priority_note);
+    }
+
+    fn mono_note_off(&mut self, midi_note: u16) {
+        self.held_notes.remove(midi_note);
+
+        match self.get_priority_note() {
+            Some(priority_note) => self.change_mono_note(priority_note),
+            None => self.voices[MONO_VOICE_INDEX].release_note(),
+        }
+    }
+
+    fn change_mono_note(&mut self, midi_note: u16) {
+        let voice = &mut self.voices[MONO_VOICE_INDEX];
+
+        if voice.midi_note == midi_note {
+            return;
+        }
+
+        voice.glide_to_note(midi_note);
+
+        if !self.legato_is_enabled {
+            voice.velocity = self.last_velocity;
+            voice.midi_state = MidiState::NoteOn;
+            voice.oscillators.start_note();
+        }
+    }
+
+    fn get_priority_note(&self) -> Option<u16> {
+        match self.note_priority {
+            NotePriority::Last => self.held_notes.latest(),
+            NotePriority::Low => self.held_notes.lowest(),
+            NotePriority::High => self.held_notes.highest(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Voice> {
+        self.voices.iter_mut()
+    }
+
+    pub fn oscillators_mut(&mut self) -> impl Iterator<Item = &mut Oscillators> {
+        self.voices.iter_mut().map(|voice| &mut voice.oscillators)
+    }
+
+    pub fn envelopes_mut(&mut self) -> impl Iterator<Item = &mut Envelope> {
+        self.voices.iter_mut().map(|voice| &mut voice.envelope)
+    }
+
+    pub fn filter_envelopes_mut(&mut self) -> impl Iterator<Item = &mut Envelope> {
+        self.voices
+            .iter_mut()
+            .map(|voice| &mut voice.filter_envelope)
+    }
+
+    pub fn filters_mut(&mut self, filter_index: i32) -> impl Iterator<Item = &mut Filter> {
+        self.voices.iter_mut().flat_map(move |voice| {
+            [
+                &mut voice.left_filters[filter_index as usize],
+                &mut voice.right_filters[filter_index as usize],
+            ]
+        })
+    }
+
+    pub fn glides_mut(&mut self) -> impl Iterator<Item = &mut Glide> {
+        self.voices.iter_mut().map(|voice| &mut voice.glide)
+    }
+
+    fn get_voice_index_for_new_note(&self, midi_note: u16) -> usize {
+        let available_voices = &self.voices[..self.number_of_voices];
+
+        if self.stealing_mode == StealingMode::SameNote
+            && let Some(index) = available_voices
+                .iter()
+                .position(|voice| voice.is_active() && voice.midi_note == midi_note)
+        {
+            return index;
+        }
+
+        if let Some(index) = available_voices.iter().position(|voice| !voice.is_active()) {
+            return index;
+        }
+
+        let voices = available_voices.iter().enumerate();
+
+        let stolen_voice = match self.stealing_mode {
+            StealingMode::Oldest | StealingMode::SameNote => {
+                voices.min_by_key(|(_, voice)| voice.note_on_order)
+            }
+            StealingMode::Quietest => voices.min_by(|(_, voice), (_, other_voice)| {
+                voice
+                    .envelope
+                    .get_current_level()
+                    .total_cmp(&other_voice.envelope.get_current_level())
+            }),
+        };
+
+        stolen_voice.map_or(0, |(index, _)| index)
+    }
+}
+
+pub fn get_stealing_mode_from_mode_name(mode_name: &str) -> StealingMode {
+    match mode_name {
+        "Oldest" => StealingMode::Oldest,
+        "Quietest" => StealingMode::Quietest,
+        "Same Note" => StealingMode::SameNote,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_voice_mode_from_mode_name(mode_name: &str) -> VoiceMode {
+    match mode_name {
+        "Poly" => VoiceMode::Poly,
+        "Mono" => VoiceMode::Mono,
+        _ => Default::default(),
+    }
+}
+
+pub fn get_note_priority_from_priority_name(priority_name: &str) -> NotePriority {
+    match priority_name {
+        "Last" => NotePriority::Last,
+        "Low" => NotePriority::Low,
+        "High" => NotePriority::High,
+        _ => Default::default(),
+    }
+}
@@ -137,12 +137,35 @@ impl UI {
         self.on_wave_shape_selected();
         self.on_wave_level_selected();
         self.on_wave_specific_parameters_selected();
+        self.on_wave_number_of_voices_changed();
+        self.on_wave_stereo_spread_changed();
+        self.on_wave_sync_source_selected();
+        self.on_wave_ring_mod_source_selected();
+        self.on_wave_fm_source_selected();
+        self.on_wave_fm_mode_selected();
+        self.on_wave_fm_amount_changed();
+        self.on_wavetable_file_selected();
+        self.on_fm_algorithm_selected();
+        self.on_fm_operator_parameter_changed();
+        self.on_additive_preset_selected();
+        self.on_additive_stretch_changed();
+        self.on_sample_file_selected();
+        self.on_sampler_root_key_changed();
+        self.on_sampler_loop_mode_selected();
+        self.on_sampler_interpolation_selected();
         self.on_wave_tuning_changed();
+        self.on_wave_octave_changed();
+        self.on_wave_fine_tune_changed();
+        self.on_sub_octave_selected();
+        self.on_wave_unison_voices_changed();
+        self.on_wave_unison_detune_changed();
+        self.on_wave_unison_width_changed();
+        self.on_wave_unison_random_phase_activated();
+        self.on_wave_filter_send_selected();
         self.on_wave_drive_amount_changed();
         self.on_wave_mod_speed_changed();
         self.on_wave_mod_amount_changed();
-        self.on_wave_detune_value_changed();
-        self.on_wave_detune_state_changed();
+        self.on_master_tune_changed();
         self.on_output_level_value_changed();
         self.on_output_level_constant_activated();
         self.on_output_pan_value_changed();
@@ -154,6 +177,17 @@ impl UI {
         self.on_filter_cutoff_value_changed();
         self.on_filter_resonance_value_changed();
         self.on_number_of_poles_selected();
+        self.on_filter_type_selected();
+        self.on_filter_model_selected();
+        self.on_filter_drive_changed();
+        self.on_filter_routing_selected();
+        self.on_filter_envelope_attack_updated();
+        self.on_filter_envelope_decay_updated();
+        self.on_filter_envelope_sustain_updated();
+        self.on_filter_envelope_release_updated();
+        self.on_filter_envelope_amount_changed();
+        self.on_filter_key_tracking_changed();
+        self.on_filter_velocity_amount_changed();
         self.on_resync_oscillators();
         self.on_resync_oscillator_lfos();
         self.on_gate_length_changed();
@@ -196,6 +230,17 @@ impl UI {
         self.on_midi_channel_selected();
         self.on_audio_device_selected();
         self.on_audio_channels_selected();
+        self.on_voice_count_changed();
+        self.on_voice_stealing_mode_selected();
+        self.on_voice_mode_selected();
+        self.on_note_priority_selected();
+        self.on_legato_activated();
+        self.on_glide_time_changed();
+        self.on_glide_curve_selected();
+        self.on_glide_mode_selected();
+        self.on_arpeggiator_glide_activated();
+        self.on_oscillator_quality_selected();
+        self.on_recording_activated();
     }
 
     fn on_wave_shape_selected(&mut self) {
@@ -224,6 +269,111 @@ impl UI {
         });
     }
 
+    fn on_wave_octave_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_octave_changed(move |octave, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorOctave(octave, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_fine_tune_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_fine_tune_changed(move |cents, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorFineTune(cents, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_sub_octave_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_sub_octave_selected(move |octave| {
+            if let Err(error) = synth_sender.send(EventType::UpdateSubOctave(octave)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_unison_voices_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_unison_voices_changed(move |number_of_voices, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorUnisonVoices(
+                number_of_voices,
+                oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_unison_detune_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_unison_detune_changed(move |detune_cents, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorUnisonDetune(
+                detune_cents,
+                oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_unison_width_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_unison_width_changed(move |width, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorUnisonWidth(width, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_unison_random_phase_activated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_unison_random_phase_activated(move |is_active, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorUnisonRandomPhase(
+                is_active, oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_filter_send_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_filter_send_selected(move |filter_send, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorFilterSend(
+                filter_send,
+                oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
     fn on_wave_level_selected(&mut self) {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
@@ -251,6 +401,213 @@ impl UI {
         });
     }
 
+    fn on_wave_number_of_voices_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_number_of_voices_changed(move |number_of_voices, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorNumberOfVoices(
+                number_of_voices,
+                oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_stereo_spread_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_stereo_spread_changed(move |stereo_spread, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorStereoSpread(
+                stereo_spread,
+                oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_fm_algorithm_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_fm_algorithm_selected(move |algorithm, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateFMAlgorithm(algorithm, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_fm_operator_parameter_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_fm_operator_parameter_changed(move |parameter, value, operator, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFMOperatorParameter(
+                parameter, value, operator, oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_additive_preset_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_additive_preset_selected(move |preset, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateAdditivePreset(preset, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_additive_stretch_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_additive_stretch_changed(move |stretch, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateAdditiveStretch(stretch, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_sample_file_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_sample_file_selected(move |path, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::LoadSample(path, oscillator)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_sampler_root_key_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_sampler_root_key_changed(move |root_key, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateSamplerRootKey(root_key, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_sampler_loop_mode_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_sampler_loop_mode_selected(move |mode, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateSamplerLoopMode(mode, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_sampler_interpolation_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_sampler_interpolation_selected(move |interpolation, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::UpdateSamplerInterpolation(
+                interpolation,
+                oscillator,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_sync_source_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_sync_source_selected(move |source, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorSyncSource(source, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_ring_mod_source_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_ring_mod_source_selected(move |source, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorRingModSource(source, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_fm_source_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_fm_source_selected(move |source, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorFMSource(source, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_fm_mode_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_fm_mode_selected(move |mode, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorFMMode(mode, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wave_fm_amount_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wave_fm_amount_changed(move |amount, oscillator| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateOscillatorFMAmount(amount, oscillator))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_wavetable_file_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_wavetable_file_selected(move |path, oscillator| {
+            if let Err(error) = synth_sender.send(EventType::LoadWavetable(path, oscillator)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
     fn on_wave_drive_amount_changed(&mut self) {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
@@ -301,28 +658,12 @@ impl UI {
         });
     }
 
-    fn on_wave_detune_value_changed(&mut self) {
+    fn on_master_tune_changed(&mut self) {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
 
-        ui.on_wave_detune_value_changed(move |detune_amount| {
-            if let Err(error) =
-                synth_sender.send(EventType::UpdateOscillatorDetuneValue(detune_amount))
-            {
-                eprintln!("Error sending event: {error}",);
-            }
-        });
-    }
-
-    fn on_wave_detune_state_changed(&mut self) {
-        let ui = self.get_ui_reference_from_ui_weak();
-        let synth_sender = self.synth_sender.clone();
-
-        ui.on_wave_detune_state_changed(move |is_active, detune_amount| {
-            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorDetuneActive(
-                is_active,
-                detune_amount,
-            )) {
+        ui.on_master_tune_changed(move |frequency| {
+            if let Err(error) = synth_sender.send(EventType::UpdateMasterTune(frequency)) {
                 eprintln!("Error sending event: {error}",);
             }
         });
@@ -420,8 +761,10 @@ impl UI {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
 
-        ui.on_filter_cutoff_value_changed(move |cutoff| {
-            if let Err(error) = synth_sender.send(EventType::UpdateFilterCutoffValue(cutoff)) {
+        ui.on_filter_cutoff_value_changed(move |cutoff, filter| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateFilterCutoffValue(cutoff, filter))
+            {
                 eprintln!("Error sending event: {error}",);
             }
         });
@@ -431,8 +774,10 @@ impl UI {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
 
-        ui.on_filter_resonance_value_changed(move |level| {
-            if let Err(error) = synth_sender.send(EventType::UpdateFilterResonanceValue(level)) {
+        ui.on_filter_resonance_value_changed(move |level, filter| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateFilterResonanceValue(level, filter))
+            {
                 eprintln!("Error sending event: {error}",);
             }
         });
@@ -442,15 +787,146 @@ impl UI {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
 
-        ui.on_number_of_poles_selected(move |number_of_poles| {
+        ui.on_number_of_poles_selected(move |number_of_poles, filter| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterNumberOfPoles(
+                number_of_poles,
+                filter,
+            )) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_type_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_type_selected(move |filter_type, filter| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterType(filter_type, filter))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_model_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_model_selected(move |model, filter| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterModel(model, filter)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_drive_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_drive_changed(move |drive, filter| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterDrive(drive, filter)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_routing_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_routing_selected(move |routing| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterRouting(routing)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_envelope_attack_updated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_envelope_attack_updated(move |milliseconds| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateFilterEnvelopeAttack(milliseconds))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_envelope_decay_updated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_envelope_decay_updated(move |milliseconds| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateFilterEnvelopeDecay(milliseconds))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_envelope_sustain_updated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_envelope_sustain_updated(move |proportion| {
             if let Err(error) =
-                synth_sender.send(EventType::UpdateFilterNumberOfPoles(number_of_poles))
+                synth_sender.send(EventType::UpdateFilterEnvelopeSustain(proportion))
             {
                 eprintln!("Error sending event: {error}",);
             }
         });
     }
 
+    fn on_filter_envelope_release_updated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_envelope_release_updated(move |milliseconds| {
+            if let Err(error) =
+                synth_sender.send(EventType::UpdateFilterEnvelopeRelease(milliseconds))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_envelope_amount_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_envelope_amount_changed(move |octaves| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterEnvelopeAmount(octaves)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_key_tracking_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_key_tracking_changed(move |amount| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterKeyTracking(amount)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_filter_velocity_amount_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_filter_velocity_amount_changed(move |amount| {
+            if let Err(error) = synth_sender.send(EventType::UpdateFilterVelocityAmount(amount)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
     fn on_resync_oscillators(&mut self) {
         let ui = self.get_ui_reference_from_ui_weak();
         let synth_sender = self.synth_sender.clone();
@@ -916,6 +1392,134 @@ impl UI {
             }
         });
     }
+
+    fn on_voice_count_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_voice_count_changed(move |number_of_voices| {
+            if let Err(error) = synth_sender.send(EventType::UpdateNumberOfVoices(number_of_voices))
+            {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_voice_stealing_mode_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_voice_stealing_mode_selected(move |mode| {
+            if let Err(error) = synth_sender.send(EventType::UpdateVoiceStealingMode(mode)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_voice_mode_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_voice_mode_selected(move |mode| {
+            if let Err(error) = synth_sender.send(EventType::UpdateVoiceMode(mode)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_note_priority_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_note_priority_selected(move |priority| {
+            if let Err(error) = synth_sender.send(EventType::UpdateNotePriority(priority)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_legato_activated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_legato_activated(move |is_active| {
+            if let Err(error) = synth_sender.send(EventType::UpdateLegatoEnabled(is_active)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_glide_time_changed(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_glide_time_changed(move |milliseconds| {
+            if let Err(error) = synth_sender.send(EventType::UpdateGlideTime(milliseconds)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_glide_curve_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_glide_curve_selected(move |curve| {
+            if let Err(error) = synth_sender.send(EventType::UpdateGlideCurve(curve)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_glide_mode_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_glide_mode_selected(move |mode| {
+            if let Err(error) = synth_sender.send(EventType::UpdateGlideMode(mode)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_arpeggiator_glide_activated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_arpeggiator_glide_activated(move |is_active| {
+            if let Err(error) = synth_sender.send(EventType::ArpeggiatorGlideEnabled(is_active)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_oscillator_quality_selected(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_oscillator_quality_selected(move |quality| {
+            if let Err(error) = synth_sender.send(EventType::UpdateOscillatorQuality(quality)) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
+
+    fn on_recording_activated(&mut self) {
+        let ui = self.get_ui_reference_from_ui_weak();
+        let synth_sender = self.synth_sender.clone();
+
+        ui.on_recording_activated(move |is_active| {
+            let event = if is_active {
+                EventType::StartRecording
+            } else {
+                EventType::StopRecording
+            };
+
+            if let Err(error) = synth_sender.send(event) {
+                eprintln!("Error sending event: {error}",);
+            }
+        });
+    }
 }
 
 fn set_audio_device_list_to_defaults(audio_device_list: &DeviceList, index: usize, ui: AppWindow) {
@@ -0,0 +1,144 @@
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+const RIFF_HEADER_LENGTH: usize = 12;
+const CHUNK_HEADER_LENGTH: usize = 8;
+
+const SIXTEEN_BIT_MAXIMUM_SAMPLE_VALUE: f32 = i16::MAX as f32;
+const TWENTY_FOUR_BIT_MAXIMUM_SAMPLE_VALUE: f32 = 8_388_607.0;
+
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
+pub enum BitDepth {
+    Sixteen,
+    TwentyFour,
+    #[default]
+    ThirtyTwoFloat,
+}
+
+pub struct WavFileWriter {
+    writer: WavWriter<BufWriter<File>>,
+    bit_depth: BitDepth,
+}
+
+impl WavFileWriter {
+    pub fn create(
+        path: &Path,
+        sample_rate: u32,
+        number_of_channels: u16,
+        bit_depth: BitDepth,
+    ) -> Result<Self, Box<dyn Error>> {
+        let (bits_per_sample, sample_format) = match bit_depth {
+            BitDepth::Sixteen => (16, SampleFormat::Int),
+            BitDepth::TwentyFour => (24, SampleFormat::Int),
+            BitDepth::ThirtyTwoFloat => (32, SampleFormat::Float),
+        };
+
+        let spec = WavSpec {
+            channels: number_of_channels,
+            sample_rate,
+            bits_per_sample,
+            sample_format,
+        };
+
+        Ok(Self {
+            writer: WavWriter::create(path, spec)?,
+            bit_depth,
+        })
+    }
+
+    pub fn write_sample(&mut self, sample: f32) -> Result<(), Box<dyn Error>> {
+        let clamped_sample = sample.clamp(-1.0, 1.0);
+
+        match self.bit_depth {
+            BitDepth::Sixteen => self
+                .writer
+                .write_sample((clamped_sample * SIXTEEN_BIT_MAXIMUM_SAMPLE_VALUE).round() as i16)?,
+            BitDepth::TwentyFour => self.writer.write_sample(
+                (clamped_sample * TWENTY_FOUR_BIT_MAXIMUM_SAMPLE_VALUE).round() as i32,
+            )?,
+            BitDepth::ThirtyTwoFloat => self.writer.write_sample(sample)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn finalize(self) -> Result<(), Box<dyn Error>> {
+        self.writer.finalize()?;
+        Ok(())
+    }
+}
+
+pub fn get_bit_depth_from_name(bit_depth_name: &str) -> Option<BitDepth> {
+    match bit_depth_name {
+        "16" => Some(BitDepth::Sixteen),
+        "24" => Some(BitDepth::TwentyFour),
+        "32" | "32f" | "32float" => Some(BitDepth::ThirtyTwoFloat),
+        _ => None,
+    }
+}
+
+// Only the first channel is kept. Returns the samples with the file's sample rate
+pub fn read_first_channel_from_file(path: &Path) -> Result<(Vec<f32>, u32), Box<dyn Error>> {
+    let mut reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    let number_of_channels = spec.channels.max(1) as usize;
+
+    let interleaved_samples: Vec<f32> = match spec.sample_format {
+        SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>()?,
+        SampleFormat::Int => {
+            let full_scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 / full_scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let samples: Vec<f32> = interleaved_samples
+        .into_iter()
+        .step_by(number_of_channels)
+        .collect();
+
+    if samples.is_empty() {
+        return Err(format!("{} contains no audio", path.display()).into());
+    }
+
+    Ok((samples, spec.sample_rate))
+}
+
+// hound skips the chunks it doesn't know, so those are found by walking the RIFF chunks directly.
+// Returns the chunk's contents, or nothing when the file doesn't have one
+pub fn read_chunk_from_file(
+    path: &Path,
+    chunk_id: &[u8; 4],
+) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    let bytes = fs::read(path)?;
+
+    if bytes.len() < RIFF_HEADER_LENGTH || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(format!("{} is not a wav file", path.display()).into());
+    }
+
+    let mut position = RIFF_HEADER_LENGTH;
+
+    while let Some(header) = bytes.get(position..position + CHUNK_HEADER_LENGTH) {
+        let chunk_length =
+            u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let chunk_start = position + CHUNK_HEADER_LENGTH;
+
+        if &header[0..4] == chunk_id {
+            return Ok(bytes
+                .get(chunk_start..chunk_start.saturating_add(chunk_length))
+                .map(|chunk| chunk.to_vec()));
+        }
+
+        // Chunks are padded to an even length
+        position = chunk_start
+            .saturating_add(chunk_length)
+            .saturating_add(chunk_length % 2);
+    }
+
+    Ok(None)
+}